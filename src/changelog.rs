@@ -0,0 +1,329 @@
+use doc::{text, Inline};
+use std::collections::HashMap;
+use types::*;
+
+/// What a change of "Recent changes" added: types or methods, by anchor, and fields, by anchor of
+/// their type or method and name.
+#[derive(Debug, Default)]
+struct Additions {
+    items: Vec<String>,
+    fields: Vec<(String, String)>,
+}
+
+enum Token {
+    Word(String),
+    /// `,` or `:`, which go on with a list.
+    Comma,
+    /// The end of a sentence, which ends any list.
+    Stop,
+    /// Emphasis or code, which the changes use for field names.
+    Name(String),
+    Link { anchor: Option<String>, text: String },
+}
+
+enum State {
+    None,
+    /// After "added the object" or "new types", before the links to the added items.
+    Items,
+    /// After "the field" or "new parameters", before the names of the added fields.
+    Fields(Vec<String>),
+    /// After "the field x to the", before the links to the types and methods `x` is added to.
+    Owners(Vec<String>),
+}
+
+/// Sets `since` on the types, methods and fields that a release added, going from the oldest
+/// release so that the first mention of an item wins.
+pub fn apply_changelog(types: &mut [Type], releases: &[TelegramRelease]) {
+    for release in releases.iter().rev() {
+        for change in &release.changes {
+            let additions = additions(change);
+            for ty in types.iter_mut() {
+                let anchor = ty.name.to_lowercase();
+                if ty.since.is_none() && additions.items.contains(&anchor) {
+                    ty.since = Some(release.version.clone());
+                }
+                for (owner, name) in &additions.fields {
+                    if *owner != anchor {
+                        continue;
+                    }
+                    for field in ty.fields.iter_mut().filter(|field| field.name == *name && field.since.is_none()) {
+                        field.since = Some(release.version.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Reads the items and fields a change added from its wording, e.g. "added the object Poll, the
+/// methods sendPoll and stopPoll and the field poll in the Message and Update objects".
+fn additions(change: &[Inline]) -> Additions {
+    let mut additions = Additions::default();
+    let mut adds = false;
+    let mut state = State::None;
+    for token in tokens(change) {
+        state = match (token, state) {
+            (Token::Word(word), state) => {
+                let word = word.as_str();
+                if word == "added" || word == "new" {
+                    adds = true;
+                }
+                match state {
+                    _ if ["added", "new", "and", "or", "the"].contains(&word) => state,
+                    State::Owners(fields) if ["object", "objects", "method", "methods"].contains(&word) => {
+                        State::Owners(fields)
+                    }
+                    _ if adds && ["object", "objects", "method", "methods", "types", "errors"].contains(&word) => {
+                        State::Items
+                    }
+                    _ if adds && ["field", "fields", "parameter", "parameters"].contains(&word) => {
+                        State::Fields(Vec::new())
+                    }
+                    State::Fields(fields) if !fields.is_empty() && (word == "to" || word == "in") => {
+                        State::Owners(fields)
+                    }
+                    _ => State::None,
+                }
+            }
+            (Token::Comma, state) => state,
+            (Token::Stop, _) => State::None,
+            (Token::Name(name), State::Fields(mut fields)) => {
+                fields.push(name);
+                State::Fields(fields)
+            }
+            (Token::Name(_), _) => State::None,
+            // a field linked to its type, as in "added the field animation to the Message object"
+            (Token::Link { text, .. }, State::Fields(mut fields)) if is_field_name(&text) => {
+                fields.push(text);
+                State::Fields(fields)
+            }
+            (Token::Link { anchor: Some(anchor), .. }, State::Items) => {
+                additions.items.push(anchor);
+                State::Items
+            }
+            (Token::Link { anchor: Some(anchor), .. }, State::Owners(fields)) => {
+                for field in &fields {
+                    additions.fields.push((anchor.clone(), field.clone()));
+                }
+                State::Owners(fields)
+            }
+            (Token::Link { .. }, _) => State::None,
+        }
+    }
+    additions
+}
+
+/// Whether `text` reads like a field or parameter name, e.g. `login_url`, rather than a type or
+/// method.
+fn is_field_name(text: &str) -> bool {
+    text.starts_with(|character: char| character.is_ascii_lowercase())
+        && text.chars().all(|character| character.is_ascii_lowercase() || character.is_ascii_digit() || character == '_')
+}
+
+fn tokens(inlines: &[Inline]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) => {
+                for word in text.split_whitespace() {
+                    let trimmed = word.trim_end_matches(|character| ",:.;".contains(character));
+                    if !trimmed.is_empty() {
+                        tokens.push(Token::Word(trimmed.to_lowercase()));
+                    }
+                    match word[trimmed.len()..].chars().next() {
+                        Some(',') | Some(':') => tokens.push(Token::Comma),
+                        Some(_) => tokens.push(Token::Stop),
+                        None => {}
+                    }
+                }
+            }
+            Inline::Emphasis(content) | Inline::Strong(content) => tokens.push(Token::Name(text(content))),
+            Inline::Code(code) => tokens.push(Token::Name(code.clone())),
+            Inline::Link { href, content } => tokens.push(Token::Link {
+                anchor: if href.starts_with('#') { Some(href[1..].to_owned()) } else { None },
+                text: text(content),
+            }),
+            Inline::Break => tokens.push(Token::Stop),
+        }
+    }
+    tokens
+}
+
+/// Cargo feature of the items a Bot API version added, e.g. `bot-api-4-2`.
+pub fn version_feature(version: &str) -> String {
+    format!("bot-api-{}", version.replace('.', "-"))
+}
+
+/// The numbers of a version such as `4.2`, or `None` if it isn't one.
+pub fn parse_version(version: &str) -> Option<Vec<u32>> {
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+fn is_newer(version: &str, baseline: &str) -> bool {
+    let parse = |version: &str| parse_version(version).unwrap_or_else(|| panic!("{:?} is not a Bot API version", version));
+    parse(version) > parse(baseline)
+}
+
+/// Every feature of `features`, oldest version first, with the features it enables: the one of
+/// the version before, so that each feature enables everything added up to its version.
+pub fn version_feature_chain<'a>(features: impl IntoIterator<Item = &'a str>) -> Vec<(&'a str, Vec<&'a str>)> {
+    let mut features: Vec<_> = features.into_iter().collect();
+    let version = |feature: &str| parse_version(&feature.trim_start_matches("bot-api-").replace('-', "."));
+    features.sort_by_key(|feature| version(feature));
+    features.dedup();
+    let mut chain = Vec::new();
+    for (index, feature) in features.iter().enumerate() {
+        chain.push((*feature, features[..index].last().cloned().into_iter().collect()));
+    }
+    chain
+}
+
+/// The features of the types, methods and fields added after `baseline`. Fields are keyed by the
+/// name of their type or method and their own.
+pub fn version_features(
+    types: &[Type],
+    baseline: &str,
+) -> (HashMap<String, String>, HashMap<(String, String), String>) {
+    let mut type_features = HashMap::new();
+    let mut field_features = HashMap::new();
+    for ty in types {
+        match &ty.since {
+            Some(since) if is_newer(since, baseline) => {
+                type_features.insert(ty.name.clone(), version_feature(since));
+            }
+            _ => {}
+        }
+        for field in &ty.fields {
+            match &field.since {
+                Some(since) if is_newer(since, baseline) => {
+                    field_features.insert((ty.name.clone(), field.name.clone()), version_feature(since));
+                }
+                _ => {}
+            }
+        }
+    }
+    (type_features, field_features)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kuchiki::traits::TendrilSink;
+    use parser::changelog_parser;
+    use test_util::*;
+
+    /// Releases of a "Recent changes" fragment, with each change as a `<li>` of the documentation.
+    fn releases(releases: &[(&str, &[&str])]) -> Vec<TelegramRelease> {
+        let mut html = String::new();
+        for (version, changes) in releases {
+            html.push_str(&format!("<h4>Date</h4><p><strong>Bot API {}</strong></p><ul>", version));
+            for change in changes.iter() {
+                html.push_str(&format!("<li>{}</li>", change));
+            }
+            html.push_str("</ul>");
+        }
+        changelog_parser(&kuchiki::parse_html().one(html)).collect()
+    }
+
+    fn with_fields(name: &str, fields: &[&str]) -> Type {
+        ty(name, TypeKind::Type, fields.iter().map(|name| field(name, optional("String"))).collect())
+    }
+
+    fn since<'a>(types: &'a [Type], name: &str, field: Option<&str>) -> Option<&'a str> {
+        let ty = types.iter().find(|ty| ty.name == name).unwrap();
+        let since = match field {
+            Some(field) => &ty.fields.iter().find(|other| other.name == field).unwrap().since,
+            None => &ty.since,
+        };
+        since.as_ref().map(String::as_str)
+    }
+
+    const BOT_API_4_0: &[&str] = &[
+        r##"Added support for <strong>editing the media content of messages</strong>: added the method <a href="#editmessagemedia">editMessageMedia</a> and new types <a href="#inputmediaanimation">InputMediaAnimation</a>, <a href="#inputmediaaudio">InputMediaAudio</a>, and <a href="#inputmediadocument">InputMediaDocument</a>."##,
+        r##"Added the method <a href="#sendanimation">sendAnimation</a>, which can be used instead of <a href="#senddocument">sendDocument</a> to send animations, specifying their duration, width and height."##,
+        r##"Added the field <a href="#animation">animation</a> to the <a href="#message">Message</a> object. For backward compatibility, when this field is set, the <em>document</em> field will be also set."##,
+        r##"Added two new <a href="#messageentity">MessageEntity</a> types: <em>cashtag</em> and <em>phone_number</em>."##,
+        r##"Added <a href="https://en.wikipedia.org/wiki/VCard">vCard</a> support when sharing contacts: added the field <em>vcard</em> to the objects <a href="#contact">Contact</a>, <a href="#inlinequeryresultcontact">InlineQueryResultContact</a>, <a href="#inputcontactmessagecontent">InputContactMessageContent</a> and the method <a href="#sendcontact">sendContact</a>."##,
+    ];
+    const BOT_API_4_1: &[&str] = &[
+        r##"Added support for translated versions of documents in <a href="/passport">Telegram Passport</a>. New field <em>translation</em> in <a href="#encryptedpassportelement">EncryptedPassportElement</a>."##,
+    ];
+    const BOT_API_4_2: &[&str] = &[
+        r##"Added support for native polls: added the object <a href="#poll">Poll</a>, the methods <a href="#sendpoll">sendPoll</a> and <a href="#stoppoll">stopPoll</a> and the field <em>poll</em> in the <a href="#message">Message</a> and <a href="#update">Update</a> objects."##,
+        r##"Added the field <em>forward_sender_name</em> to the <a href="#message">Message</a> object, containing name of the sender who has opted to hide their account."##,
+    ];
+    const BOT_API_4_3: &[&str] = &[
+        r##"Added the new object <a href="#loginurl">LoginUrl</a> and the new field <em>login_url</em> to the <a href="#inlinekeyboardbutton">InlineKeyboardButton</a> object which allows to <strong>automatically authorize</strong> users before they go to a URL specified by the bot."##,
+        r##"Added the field <code>reply_markup</code> to the <a href="#message">Message</a> object, containing the inline keyboard attached to the message."##,
+        r##"Bots now receive the <em>edited_message</em> <a href="#update">Update</a> even if only <em>Message.reply_markup</em> has changed."##,
+    ];
+
+    #[test]
+    fn reads_added_items_and_fields() {
+        let releases = releases(&[("4.2", BOT_API_4_2)]);
+        let additions = additions(&releases[0].changes[0]);
+        assert_eq!(additions.items, vec!["poll", "sendpoll", "stoppoll"]);
+        let owners = vec![("message".to_owned(), "poll".to_owned()), ("update".to_owned(), "poll".to_owned())];
+        assert_eq!(additions.fields, owners);
+    }
+
+    #[test]
+    fn reads_linked_field_names_as_fields() {
+        let releases = releases(&[("4.0", BOT_API_4_0)]);
+        let animation = additions(&releases[0].changes[2]);
+        assert!(animation.items.is_empty(), "{:?}", animation.items);
+        assert_eq!(animation.fields, vec![("message".to_owned(), "animation".to_owned())]);
+        let entity_types = additions(&releases[0].changes[3]);
+        assert!(entity_types.items.is_empty() && entity_types.fields.is_empty(), "{:?}", entity_types);
+    }
+
+    #[test]
+    fn dates_items_by_the_release_that_added_them() {
+        let mut types = vec![
+            with_fields("Message", &["text", "animation", "document", "poll", "forward_sender_name", "reply_markup"]),
+            with_fields("Update", &["update_id", "poll", "edited_message"]),
+            with_fields("Animation", &["file_id"]),
+            with_fields("Poll", &["id"]),
+            with_fields("Contact", &["vcard"]),
+            with_fields("SendContact", &["vcard"]),
+            with_fields("SendAnimation", &["animation"]),
+            with_fields("SendDocument", &["document"]),
+            with_fields("InputMediaAnimation", &["media"]),
+            with_fields("EncryptedPassportElement", &["translation"]),
+            with_fields("InlineKeyboardButton", &["url", "login_url"]),
+            with_fields("LoginUrl", &["url"]),
+        ];
+        let releases = releases(&[("4.3", BOT_API_4_3), ("4.2", BOT_API_4_2), ("4.1", BOT_API_4_1), ("4.0", BOT_API_4_0)]);
+        apply_changelog(&mut types, &releases);
+        for name in &["Message", "Update", "Animation", "SendDocument", "Contact", "InlineKeyboardButton"] {
+            assert_eq!(since(&types, name, None), None, "{}", name);
+        }
+        assert_eq!(since(&types, "Poll", None), Some("4.2"));
+        assert_eq!(since(&types, "SendAnimation", None), Some("4.0"));
+        assert_eq!(since(&types, "InputMediaAnimation", None), Some("4.0"));
+        assert_eq!(since(&types, "LoginUrl", None), Some("4.3"));
+        assert_eq!(since(&types, "Message", Some("animation")), Some("4.0"));
+        assert_eq!(since(&types, "Message", Some("document")), None);
+        assert_eq!(since(&types, "Message", Some("poll")), Some("4.2"));
+        assert_eq!(since(&types, "Message", Some("forward_sender_name")), Some("4.2"));
+        assert_eq!(since(&types, "Message", Some("reply_markup")), Some("4.3"));
+        assert_eq!(since(&types, "Update", Some("poll")), Some("4.2"));
+        assert_eq!(since(&types, "Update", Some("edited_message")), None);
+        assert_eq!(since(&types, "SendContact", Some("vcard")), Some("4.0"));
+        assert_eq!(since(&types, "EncryptedPassportElement", Some("translation")), Some("4.1"));
+        assert_eq!(since(&types, "InlineKeyboardButton", Some("login_url")), Some("4.3"));
+    }
+
+    #[test]
+    fn version_features_enable_the_previous_version() {
+        let chain = version_feature_chain(vec!["bot-api-4-10", "bot-api-4-2", "bot-api-4-9", "bot-api-4-2"]);
+        assert_eq!(chain, vec![
+            ("bot-api-4-2", vec![]),
+            ("bot-api-4-9", vec!["bot-api-4-2"]),
+            ("bot-api-4-10", vec!["bot-api-4-9"]),
+        ]);
+        assert!(is_newer("4.10", "4.9"));
+        assert!(!is_newer("4.0", "4.0"));
+        assert_eq!(parse_version("4.x"), None);
+    }
+}
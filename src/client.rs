@@ -0,0 +1,224 @@
+use codegen::{Impl, Scope};
+use doc::Doc;
+use generator::{cfg_attributes, cfg_feature, group_by_features, push_impl, Context, Generator, DESERIALIZE_METHODS};
+use std::collections::HashSet;
+use types::*;
+use utils::{camel_case, doc_url, snake_case};
+
+pub struct ClientMethod {
+    pub name: String,
+    pub docs: Doc,
+    pub return_type: FieldType,
+}
+
+pub fn client_methods(types: &[Type]) -> Vec<ClientMethod> {
+    types
+        .iter()
+        .filter_map(|ty| match &ty.kind {
+            TypeKind::Method(return_type) => Some(ClientMethod {
+                name: ty.name.clone(),
+                docs: ty.docs.clone(),
+                return_type: return_type.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generates the `client` modules: a `Bot` with one function per method, the `BotApi` trait it
+/// shares with `MockBot`, and the transports.
+pub fn generate_client(methods: Vec<ClientMethod>, modules: &mut HashSet<Module>, context: &Context) {
+    let methods: Vec<_> = methods
+        .into_iter()
+        .map(|method| {
+            let return_type = method.return_type.clone().generate(modules, context);
+            (method, return_type)
+        })
+        .collect();
+    let mut scope = Scope::new();
+    for (features, methods) in group_by_features(&methods, |(method, _)| context.features(&method.name)) {
+        let mut bot_impl = Impl::new("Bot");
+        bot_impl.generic("T: Transport").target_generic("T");
+        for (method, return_type) in methods {
+            bot_impl
+                .new_fn(&snake_case(&method.name))
+                .doc(&method.docs.item_markdown(&method.name, &context.links))
+                .vis("pub")
+                .arg_ref_self()
+                .arg("method", &method.name)
+                .ret(&format!("impl Future<Output = Result<{}, Error>> + '_", return_type))
+                .line("self.execute(method)");
+        }
+        push_impl(&mut scope, &features, bot_impl);
+    }
+    let mut contents = include_str!("../templates/client/bot.rs").to_owned();
+    contents.push('\n');
+    contents.push_str(&scope.to_string());
+    modules.insert(client_module("bot", "*", contents, None));
+    modules.insert(generate_bot_api(&methods, context));
+    modules.insert(generate_any_method(&methods, context));
+    let templates = [
+        ("transport", include_str!("../templates/client/transport.rs"), None),
+        ("error", include_str!("../templates/client/error.rs"), None),
+        ("retry", include_str!("../templates/client/retry.rs"), None),
+        ("mock", include_str!("../templates/client/mock.rs"), None),
+        ("update_stream", include_str!("../templates/client/update_stream.rs"), None),
+        ("webhook", include_str!("../templates/client/webhook.rs"), None),
+        ("hyper_webhook", include_str!("../templates/client/hyper_webhook.rs"), Some("hyper")),
+        ("hyper_transport", include_str!("../templates/client/hyper_transport.rs"), Some("hyper")),
+        ("blocking", include_str!("../templates/client/blocking.rs"), Some("blocking")),
+    ];
+    for (module_name, contents, feature) in templates.iter() {
+        modules.insert(client_module(module_name, "*", contents.to_string(), *feature));
+    }
+}
+
+/// The `BotApi` trait, implemented by `Bot` over HTTP and by `MockBot` for unit tests.
+///
+/// Written without codegen, which has no attributes on functions, so that every function can be
+/// behind the features of its method.
+fn generate_bot_api(methods: &[(ClientMethod, String)], context: &Context) -> Module {
+    let mut contents = String::from("use crate::client::*;\nuse crate::methods::*;\nuse crate::types::*;\n\n");
+    contents.push_str("/// Every Bot API method, to write code that works the same with a `Bot` and a `MockBot`.\n");
+    contents.push_str("pub trait BotApi {\n");
+    contents.push_str(&bot_api_fns(methods, context, None));
+    contents.push_str("}\n\nimpl<T: Transport> BotApi for Bot<T> {\n");
+    contents.push_str(&bot_api_fns(methods, context, Some("Box::pin(self.execute(method))")));
+    contents.push_str("}\n\nimpl BotApi for MockBot {\n");
+    contents.push_str(&bot_api_fns(methods, context, Some("self.call(method)")));
+    contents.push_str("}\n");
+    client_module("bot_api", "*", contents, None)
+}
+
+/// The functions of `BotApi`, documented in the trait, and calling `body` in its impls.
+fn bot_api_fns(methods: &[(ClientMethod, String)], context: &Context, body: Option<&str>) -> String {
+    let mut fns = Vec::new();
+    for (method, return_type) in methods {
+        let mut item = String::new();
+        if body.is_none() {
+            for line in method.docs.item_markdown(&method.name, &context.links).lines() {
+                item.push_str(&format!("    /// {}\n", line));
+            }
+        }
+        for feature in context.features(&method.name) {
+            item.push_str(&format!("    #[{}]\n", cfg_feature(feature)));
+        }
+        let signature = format!(
+            "fn {}(&self, method: {}) -> BoxFuture<'_, Result<{}, Error>>",
+            snake_case(&method.name),
+            method.name,
+            return_type
+        );
+        match body {
+            Some(body) => item.push_str(&format!("    {} {{\n        {}\n    }}\n", signature, body)),
+            None => item.push_str(&format!("    {};\n", signature)),
+        }
+        fns.push(item);
+    }
+    fns.join("\n")
+}
+
+/// `AnyMethod`, an enum with every method as a variant of the same name.
+fn generate_any_method(methods: &[(ClientMethod, String)], context: &Context) -> Module {
+    let mut scope = Scope::new();
+    {
+        let any_method = scope
+            .new_enum("AnyMethod")
+            .doc("A call to any method, serialized with the method name in the `method` field.")
+            .derive("Debug")
+            .derive("Clone")
+            .derive("PartialEq")
+            .derive("Serialize")
+            .vis("pub")
+            .annotation(vec![r#"serde(tag = "method", rename_all = "camelCase")"#]);
+        any_method.push_annotation(DESERIALIZE_METHODS);
+        for (method, _) in methods {
+            let cfg = cfg_attributes(&context.features(&method.name));
+            any_method.new_variant(&format!("{}{}", cfg, method.name)).tuple(&method.name);
+        }
+    }
+    {
+        let name = scope
+            .new_impl("AnyMethod")
+            .new_fn("name")
+            .doc("Name of the method, e.g. `sendMessage`.")
+            .vis("pub")
+            .arg_ref_self()
+            .ret("&'static str")
+            .line("match self {");
+        for (method, _) in methods {
+            let cfg = cfg_attributes(&context.features(&method.name));
+            name.line(&format!("    {}AnyMethod::{}(_) => {}::name(),", cfg, method.name, method.name));
+        }
+        name.line("}");
+    }
+    for (method, _) in methods {
+        let features = context.features(&method.name);
+        let mut from_impl = Impl::new("AnyMethod");
+        from_impl
+            .impl_trait(&format!("From<{}>", method.name))
+            .new_fn("from")
+            .arg("method", &method.name)
+            .ret("Self")
+            .line(&format!("AnyMethod::{}(method)", method.name));
+        push_impl(&mut scope, &features, from_impl);
+        let mut variant_impl = Impl::new(&method.name);
+        variant_impl
+            .impl_trait("AnyMethodVariant")
+            .new_fn("from_any")
+            .arg("method", "&AnyMethod")
+            .ret("Option<&Self>")
+            .line("match method {")
+            .line(&format!("    AnyMethod::{}(method) => Some(method),", method.name))
+            .line("    _ => None,")
+            .line("}");
+        push_impl(&mut scope, &features, variant_impl);
+    }
+    let mut contents = include_str!("../templates/client/any_method.rs").to_owned();
+    contents.push('\n');
+    contents.push_str(&scope.to_string());
+    client_module("any_method", "*", contents, None)
+}
+
+/// The `METHODS` registry, describing every method as documented.
+pub fn generate_method_registry(types: &[Type]) -> Module {
+    let mut registry = String::from("pub static METHODS: &[MethodInfo] = &[\n");
+    for ty in types {
+        let return_type = match &ty.kind {
+            TypeKind::Method(return_type) => return_type,
+            _ => continue,
+        };
+        let name = camel_case(&ty.name);
+        registry.push_str("    MethodInfo {\n");
+        registry.push_str(&format!("        name: {:?},\n", name));
+        registry.push_str("        params: &[\n");
+        for field in &ty.fields {
+            registry.push_str("            ParamInfo {\n");
+            registry.push_str(&format!("                name: {:?},\n", field.name));
+            registry.push_str(&format!("                telegram_type: {:?},\n", field.field_type.telegram_type));
+            registry.push_str(&format!("                required: {},\n", !field.field_type.is_optional));
+            registry.push_str(&format!("                doc: {:?},\n", field.doc.text()));
+            registry.push_str("            },\n");
+        }
+        registry.push_str("        ],\n");
+        registry.push_str(&format!("        return_type: {:?},\n", return_type.telegram_type));
+        registry.push_str(&format!("        doc_url: {:?},\n", doc_url(&name)));
+        registry.push_str("    },\n");
+    }
+    registry.push_str("];\n");
+    let mut contents = include_str!("../templates/client/registry.rs").to_owned();
+    contents.push('\n');
+    contents.push_str(&registry);
+    client_module("registry", "*", contents, None)
+}
+
+pub fn client_module(module_name: &str, module_type: &str, contents: String, feature: Option<&str>) -> Module {
+    Module {
+        kind: TypeKind::Client,
+        contents,
+        module_name: module_name.to_owned(),
+        module_type: module_type.to_owned(),
+        features: feature.into_iter().map(ToOwned::to_owned).collect(),
+        section: None,
+    }
+}
@@ -10,6 +10,8 @@ impl From<TelegramMethod> for Type {
         Self {
             name,
             docs: method.docs,
+            section: method.section,
+            since: None,
             fields,
             kind: TypeKind::Method(return_type),
         }
@@ -22,6 +24,8 @@ impl From<TelegramType> for Type {
         Self {
             name: telegram_type.name,
             docs: telegram_type.docs,
+            section: telegram_type.section,
+            since: None,
             fields,
             kind: TypeKind::Type,
         }
@@ -30,11 +34,15 @@ impl From<TelegramType> for Type {
 
 impl From<TelegramField> for Field {
     fn from(field: TelegramField) -> Self {
-        let field_type = field.telegram_type.into_field_type(&field.name);
+        let mut field_type = field.telegram_type.into_field_type(&field.name);
+        if field_type.name == "Integer" && field.doc.text().to_lowercase().contains("unix time") {
+            field_type.name = "UnixTime".to_owned();
+        }
         Self {
             doc: field.doc,
             name: field.name,
             field_type,
+            since: None,
         }
     }
 }
@@ -50,8 +58,12 @@ impl From<TelegramTypeOrMethod> for Type {
 
 impl TelegramFieldType {
     fn into_field_type(self, field_name: &str) -> FieldType {
+        let telegram_type = self.name.clone();
         let array_count = self.name.matches("Array of ").count();
         let mut type_name = self.name.replacen("Array of ", "", array_count);
+        if type_name == "InputFile or String" {
+            type_name = "InputFile".to_owned();
+        }
         let contains_or = type_name.contains(" or ");
         let kind = if contains_or || type_name.contains(" and ") {
             let mut variants;
@@ -80,7 +92,7 @@ impl TelegramFieldType {
             FieldKind::Enum(variants)
         } else {
             if type_name == "String" && field_name == "media" {
-                type_name = "InputFileOrString".to_string()
+                type_name = "InputFile".to_string()
             }
             FieldKind::Simple
         };
@@ -88,8 +100,10 @@ impl TelegramFieldType {
             is_boxed: false,
             array_count,
             doc: None,
+            section: None,
             kind,
             name: type_name,
+            telegram_type,
             is_optional: self.is_optional,
         }
     }
@@ -0,0 +1,143 @@
+//! What every heading of the documentation became, to find the items the parser skips or pairs
+//! with the wrong table.
+
+use kuchiki::traits::TendrilSink;
+use model::Model;
+use parser::heading_parser;
+use types::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Classification {
+    Type,
+    Method,
+    Union,
+    /// A section or note of the documentation, not an item.
+    Prose,
+    /// Named like a type or method, but nothing was generated for it.
+    Unparsed,
+}
+
+pub struct Coverage {
+    pub headings: Vec<(TelegramHeading, Classification)>,
+    pub warnings: Vec<String>,
+}
+
+pub fn coverage(html: String) -> Coverage {
+    let document = kuchiki::parse_html().one(html);
+    let model = Model::from_document(&document);
+    let mut warnings = Vec::new();
+    let headings: Vec<_> = heading_parser(&document)
+        .map(|heading| {
+            let classification = classify(&heading, &model);
+            if classification == Classification::Unparsed {
+                warnings.push(format!(
+                    "`{}` (#{}) looks like an API item but produced no module",
+                    heading.text, heading.anchor
+                ));
+            }
+            (heading, classification)
+        })
+        .collect();
+    // a type or method named after no heading got the name of another table
+    let names = model.types.iter().map(|ty| &ty.name).chain(model.unions.iter().map(|union| &union.name));
+    for name in names {
+        if !headings.iter().any(|(heading, _)| heading.anchor == name.to_lowercase()) {
+            warnings.push(format!("`{}` was generated but matches no heading", name));
+        }
+    }
+    Coverage { headings, warnings }
+}
+
+fn classify(heading: &TelegramHeading, model: &Model) -> Classification {
+    if heading.level == 4 {
+        if let Some(ty) = model.types.iter().find(|ty| ty.name.to_lowercase() == heading.anchor) {
+            return match ty.kind {
+                TypeKind::Method(_) => Classification::Method,
+                _ => Classification::Type,
+            };
+        }
+        if model.unions.iter().any(|union| union.name.to_lowercase() == heading.anchor) {
+            return Classification::Union;
+        }
+    }
+    let mut characters = heading.text.chars();
+    let is_identifier = characters.next().map_or(false, |first| first.is_ascii_alphabetic())
+        && characters.all(|character| character.is_ascii_alphanumeric());
+    if heading.level == 4 && is_identifier {
+        Classification::Unparsed
+    } else {
+        Classification::Prose
+    }
+}
+
+impl Coverage {
+    /// One line per heading, the h4 under their h3.
+    pub fn report(&self) -> String {
+        self.headings
+            .iter()
+            .map(|(heading, classification)| {
+                let indent = if heading.level == 4 { "  " } else { "" };
+                format!("{}{} (#{}): {:?}\n", indent, heading.text, heading.anchor, classification)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heading(level: u8, anchor: &str, text: &str) -> String {
+        let anchor = format!(r##"<a class="anchor" name="{}" href="#{}"><i class="anchor-icon"></i></a>"##, anchor, anchor);
+        format!("<h{}>{}{}</h{}>\n", level, anchor, text, level)
+    }
+
+    fn table(header: &str, rows: &[&str]) -> String {
+        format!("<table class=\"table\">\n<thead>\n<tr>{}</tr>\n</thead>\n<tbody>\n{}</tbody>\n</table>\n", header, rows.concat())
+    }
+
+    #[test]
+    fn classifies_documentation_headings() {
+        let mut html = heading(3, "available-types", "Available types");
+        html.push_str("<p>All types used in the Bot API responses are represented as JSON-objects.</p>\n");
+        html.push_str(&heading(4, "user", "User"));
+        html.push_str("<p>This object represents a Telegram user or bot.</p>\n");
+        html.push_str(&table(
+            "<th>Field</th><th>Type</th><th>Description</th>",
+            &["<tr><td>id</td><td>Integer</td><td>Unique identifier for this user or bot</td></tr>\n"],
+        ));
+        html.push_str(&heading(4, "inputfile", "InputFile"));
+        html.push_str("<p>This object represents the contents of a file to be uploaded.</p>\n");
+        html.push_str(&heading(4, "sending-files", "Sending files"));
+        html.push_str("<p>There are three ways to send files.</p>\n");
+        html.push_str(&heading(3, "available-methods", "Available methods"));
+        html.push_str(&heading(4, "getme", "getMe"));
+        html.push_str("<p>A simple method for testing your bot&#39;s auth token. Requires no parameters.</p>\n");
+        html.push_str(&heading(4, "sendmessage", "sendMessage"));
+        html.push_str("<p>Use this method to send text messages. On success, the sent <a href=\"#message\">Message</a> is returned.</p>\n");
+        html.push_str(&table(
+            "<th>Parameter</th><th>Type</th><th>Required</th><th>Description</th>",
+            &["<tr><td>text</td><td>String</td><td>Yes</td><td>Text of the message to be sent</td></tr>\n"],
+        ));
+        let coverage = coverage(html);
+        let classifications: Vec<_> = coverage
+            .headings
+            .iter()
+            .map(|(heading, classification)| (heading.text.as_str(), *classification))
+            .collect();
+        assert_eq!(classifications, vec![
+            ("Available types", Classification::Prose),
+            ("User", Classification::Type),
+            ("InputFile", Classification::Unparsed),
+            ("Sending files", Classification::Prose),
+            ("Available methods", Classification::Prose),
+            ("getMe", Classification::Unparsed),
+            ("sendMessage", Classification::Method),
+        ]);
+        assert_eq!(coverage.warnings, vec![
+            "`InputFile` (#inputfile) looks like an API item but produced no module",
+            "`getMe` (#getme) looks like an API item but produced no module",
+        ]);
+        assert!(coverage.report().contains("  getMe (#getme): Unparsed\n"));
+    }
+}
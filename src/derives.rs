@@ -0,0 +1,158 @@
+use graph::Node;
+use std::collections::{HashMap, HashSet};
+
+/// Structs with more fields than this are never made `Copy`, even when every field is.
+const MAX_COPY_FIELDS: usize = 4;
+
+struct Traits {
+    eq_hash: bool,
+    copy: bool,
+}
+
+fn primitive(name: &str) -> Option<Traits> {
+    let (eq_hash, copy) = match name {
+        "Integer" | "Int" | "UnixTime" | "Boolean" | "True" => (true, true),
+        "Float" | "Float number" => (false, true),
+        "String" => (true, false),
+        _ => return None,
+    };
+    Some(Traits { eq_hash, copy })
+}
+
+/// Works out which of `Clone`, `PartialEq`, `Eq`, `Hash`, `Copy` and `Default` every generated
+/// struct and union can derive, by looking at its fields transitively. The unions in `fallbacks`
+/// have an `Unknown` variant holding any JSON value.
+pub fn infer_derives(nodes: &HashMap<&str, Node>, fallbacks: &HashSet<String>) -> HashMap<String, Vec<&'static str>> {
+    let has_fallback = |name: &str| fallbacks.contains(name);
+    let eq_hash = fixpoint(nodes, |name, traits| {
+        match &nodes[name] {
+            Node::Struct(fields) => fields.iter().all(|field| traits(&field.name).eq_hash),
+            Node::Union(variants) => !has_fallback(name) && variants.iter().all(|(_, variant)| traits(variant).eq_hash),
+        }
+    });
+    let copy = fixpoint(nodes, |name, traits| {
+        match &nodes[name] {
+            Node::Struct(fields) => {
+                fields.len() <= MAX_COPY_FIELDS
+                    && fields.iter().all(|field| field.array_count == 0 && traits(&field.name).copy)
+            }
+            Node::Union(variants) => !has_fallback(name) && variants.iter().all(|(_, variant)| traits(variant).copy),
+        }
+    });
+
+    nodes
+        .iter()
+        .map(|(&name, node)| {
+            let mut derives = vec!["Clone", "PartialEq"];
+            if eq_hash[name] {
+                derives.push("Eq");
+                derives.push("Hash");
+            }
+            if copy[name] {
+                derives.push("Copy");
+            }
+            if let Node::Struct(fields) = node {
+                if fields.iter().all(|field| field.is_optional || field.array_count != 0) {
+                    derives.push("Default");
+                }
+            }
+            (name.to_owned(), derives)
+        })
+        .collect()
+}
+
+/// Computes the greatest fixpoint of `rule` over every node, so that recursive types such as
+/// `Message` only lose a trait when something they contain really lacks it. `rule` sees both
+/// traits of primitives, and the value being computed for everything else.
+fn fixpoint<'a>(
+    nodes: &HashMap<&'a str, Node<'a>>,
+    rule: impl Fn(&str, &dyn Fn(&str) -> Traits) -> bool,
+) -> HashMap<&'a str, bool> {
+    let mut values: HashMap<&str, bool> = nodes.keys().map(|&name| (name, true)).collect();
+    loop {
+        let mut changed = false;
+        for &name in nodes.keys() {
+            if !values[name] {
+                continue;
+            }
+            let value = {
+                let traits = |name: &str| match primitive(name) {
+                    Some(traits) => traits,
+                    None => {
+                        let value = values.get(name).cloned().unwrap_or(false);
+                        Traits {
+                            eq_hash: value,
+                            copy: value,
+                        }
+                    }
+                };
+                rule(name, &traits)
+            };
+            if !value {
+                values.insert(name, false);
+                changed = true;
+            }
+        }
+        if !changed {
+            return values;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::type_graph;
+    use test_util::*;
+    use types::*;
+
+    #[test]
+    fn derives_what_every_field_allows() {
+        let photos = FieldType { array_count: 2, ..field_type("PhotoSize") };
+        let types = vec![
+            ty("Location", TypeKind::Type, vec![field("longitude", field_type("Float")), field("latitude", field_type("Float"))]),
+            ty("User", TypeKind::Type, vec![field("id", field_type("Integer")), field("username", optional("String"))]),
+            ty("UserProfilePhotos", TypeKind::Type, vec![field("photos", photos)]),
+            ty("PhotoSize", TypeKind::Type, vec![field("file_id", field_type("String")), field("file_size", optional("Integer"))]),
+            ty("Venue", TypeKind::Type, vec![field("location", field_type("Location")), field("title", field_type("String"))]),
+            ty("SendDocument", TypeKind::Type, vec![field("document", field_type("InputFile"))]),
+        ];
+        let derives = infer_derives(&type_graph(&types, &[]), &HashSet::new());
+        assert_eq!(derives["Location"], &["Clone", "PartialEq", "Copy"]);
+        assert_eq!(derives["User"], &["Clone", "PartialEq", "Eq", "Hash"]);
+        assert_eq!(derives["UserProfilePhotos"], &["Clone", "PartialEq", "Eq", "Hash", "Default"]);
+        assert_eq!(derives["Venue"], &["Clone", "PartialEq"]);
+        assert_eq!(derives["SendDocument"], &["Clone", "PartialEq"]);
+    }
+
+    #[test]
+    fn recursive_types_keep_traits_their_cycle_allows() {
+        let types = vec![
+            ty(
+                "Message",
+                TypeKind::Type,
+                vec![
+                    field("message_id", field_type("Integer")),
+                    field("text", optional("String")),
+                    field("reply_to_message", optional("Message")),
+                    field("chat", field_type("Chat")),
+                    field("forward_from_chat", optional("Chat")),
+                ],
+            ),
+            ty("Chat", TypeKind::Type, vec![field("id", field_type("Integer")), field("pinned_message", optional("Message"))]),
+        ];
+        let derives = infer_derives(&type_graph(&types, &[]), &HashSet::new());
+        assert_eq!(derives["Message"], &["Clone", "PartialEq", "Eq", "Hash"]);
+        assert_eq!(derives["Chat"], &["Clone", "PartialEq", "Eq", "Hash"]);
+    }
+
+    #[test]
+    fn unions_with_a_fallback_lose_eq_hash_and_copy() {
+        let unions = vec![union("IntegerOrBoolean", &["Integer", "Boolean"])];
+        let exhaustive = infer_derives(&type_graph(&[], &unions), &HashSet::new());
+        assert_eq!(exhaustive["IntegerOrBoolean"], &["Clone", "PartialEq", "Eq", "Hash", "Copy"]);
+        let fallbacks: HashSet<_> = vec!["IntegerOrBoolean".to_owned()].into_iter().collect();
+        let non_exhaustive = infer_derives(&type_graph(&[], &unions), &fallbacks);
+        assert_eq!(non_exhaustive["IntegerOrBoolean"], &["Clone", "PartialEq"]);
+    }
+}
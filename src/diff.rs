@@ -0,0 +1,353 @@
+//! Differences between two versions of the API, classified by whether they break code using the
+//! generated crate:
+//!
+//! - removing anything, changing the type of a field or the result of a method, and flipping a
+//!   field between optional and required, which changes its getter or builder, are breaking;
+//! - adding a required parameter breaks the builder of a method, adding an optional one doesn't;
+//! - adding any field to a type clients send changes the arguments of its `new`, while adding one
+//!   to a type only received breaks nothing;
+//! - adding a variant to a union the Bot API sends breaks exhaustive matches on it, unless the
+//!   crate is generated with `--non-exhaustive`; unions only sent to it are built, not matched;
+//! - adding a type or method is not breaking.
+
+use generator::serde_derives;
+use model::Model;
+use options::Options;
+use std::collections::HashSet;
+use types::*;
+use utils::camel_case;
+
+#[derive(Debug, Serialize)]
+pub struct Change {
+    /// The type, method or union changed.
+    pub item: String,
+    /// The field or parameter changed, if the change is about one.
+    pub field: Option<String>,
+    pub kind: ChangeKind,
+    pub breaking: bool,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    FieldAdded,
+    FieldRemoved,
+    OptionalityChanged,
+    TypeChanged,
+    ReturnTypeChanged,
+    VariantAdded,
+    VariantRemoved,
+}
+
+/// Every change from `old` to `new`, in the order of the new documentation, for a crate generated
+/// with `options` where the Bot API sends `return_types`.
+pub fn diff(old: &Model, new: &Model, return_types: &HashSet<String>, options: &Options) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for new_type in &new.types {
+        match old.types.iter().find(|old_type| old_type.name == new_type.name) {
+            Some(old_type) => diff_type(old_type, new_type, return_types, &mut changes),
+            None => changes.push(Change {
+                item: display_name(new_type),
+                field: None,
+                kind: ChangeKind::Added,
+                breaking: false,
+                description: format!("Added the {} `{}`.", kind_name(new_type), display_name(new_type)),
+            }),
+        }
+    }
+    for old_type in &old.types {
+        if !new.types.iter().any(|new_type| new_type.name == old_type.name) {
+            changes.push(Change {
+                item: display_name(old_type),
+                field: None,
+                kind: ChangeKind::Removed,
+                breaking: true,
+                description: format!("Removed the {} `{}`.", kind_name(old_type), display_name(old_type)),
+            });
+        }
+    }
+    for new_union in &new.unions {
+        match old.unions.iter().find(|old_union| old_union.name == new_union.name) {
+            Some(old_union) => {
+                let is_matched = return_types.contains(&new_union.name) && !options.non_exhaustive;
+                diff_union(old_union, new_union, is_matched, &mut changes)
+            }
+            None => changes.push(Change {
+                item: new_union.name.clone(),
+                field: None,
+                kind: ChangeKind::Added,
+                breaking: false,
+                description: format!("Added the union `{}`.", new_union.name),
+            }),
+        }
+    }
+    for old_union in &old.unions {
+        if !new.unions.iter().any(|new_union| new_union.name == old_union.name) {
+            changes.push(Change {
+                item: old_union.name.clone(),
+                field: None,
+                kind: ChangeKind::Removed,
+                breaking: true,
+                description: format!("Removed the union `{}`.", old_union.name),
+            });
+        }
+    }
+    changes
+}
+
+fn diff_type(old: &Type, new: &Type, return_types: &HashSet<String>, changes: &mut Vec<Change>) {
+    let item = display_name(new);
+    let field_kind = match new.kind {
+        TypeKind::Method(_) => "parameter",
+        _ => "field",
+    };
+    if let (TypeKind::Method(old_return), TypeKind::Method(new_return)) = (&old.kind, &new.kind) {
+        if rust_type(old_return) != rust_type(new_return) {
+            changes.push(Change {
+                item: item.clone(),
+                field: None,
+                kind: ChangeKind::ReturnTypeChanged,
+                breaking: true,
+                description: format!(
+                    "`{}` now returns {} instead of {}.",
+                    item, new_return.telegram_type, old_return.telegram_type
+                ),
+            });
+        }
+    }
+    for new_field in &new.fields {
+        let path = format!("{}.{}", item, new_field.name);
+        let old_field = match old.fields.iter().find(|old_field| old_field.name == new_field.name) {
+            Some(old_field) => old_field,
+            None => {
+                let is_optional = new_field.field_type.is_optional;
+                // types clients send have a `new` taking every field, methods have a builder
+                // taking the required ones
+                let breaking = match new.kind {
+                    TypeKind::Method(_) => !is_optional,
+                    _ => serde_derives(&new.name, return_types).0,
+                };
+                changes.push(Change {
+                    item: item.clone(),
+                    field: Some(new_field.name.clone()),
+                    kind: ChangeKind::FieldAdded,
+                    breaking,
+                    description: format!(
+                        "Added the {} {} `{}` ({}).",
+                        if is_optional { "optional" } else { "required" },
+                        field_kind,
+                        path,
+                        new_field.field_type.telegram_type
+                    ),
+                });
+                continue;
+            }
+        };
+        let (old_type, new_type) = (&old_field.field_type, &new_field.field_type);
+        if old_type.is_optional != new_type.is_optional {
+            changes.push(Change {
+                item: item.clone(),
+                field: Some(new_field.name.clone()),
+                kind: ChangeKind::OptionalityChanged,
+                breaking: true,
+                description: format!(
+                    "`{}` is now {}.",
+                    path,
+                    if new_type.is_optional { "optional" } else { "required" }
+                ),
+            });
+        }
+        if rust_type(old_type) != rust_type(new_type) {
+            changes.push(Change {
+                item: item.clone(),
+                field: Some(new_field.name.clone()),
+                kind: ChangeKind::TypeChanged,
+                breaking: true,
+                description: format!(
+                    "`{}` is now {} instead of {}.",
+                    path, new_type.telegram_type, old_type.telegram_type
+                ),
+            });
+        }
+    }
+    for old_field in &old.fields {
+        if !new.fields.iter().any(|new_field| new_field.name == old_field.name) {
+            changes.push(Change {
+                item: item.clone(),
+                field: Some(old_field.name.clone()),
+                kind: ChangeKind::FieldRemoved,
+                breaking: true,
+                description: format!("Removed the {} `{}.{}`.", field_kind, item, old_field.name),
+            });
+        }
+    }
+}
+
+/// Changes of the variants of a union; new ones only break code that `is_matched` exhaustively.
+fn diff_union(old: &FieldType, new: &FieldType, is_matched: bool, changes: &mut Vec<Change>) {
+    let (old_variants, new_variants) = match (&old.kind, &new.kind) {
+        (FieldKind::Enum(old_variants), FieldKind::Enum(new_variants)) => (old_variants, new_variants),
+        _ => return,
+    };
+    for (variant, _) in new_variants.iter().filter(|variant| !old_variants.contains(variant)) {
+        changes.push(Change {
+            item: new.name.clone(),
+            field: Some(variant.clone()),
+            kind: ChangeKind::VariantAdded,
+            breaking: is_matched,
+            description: format!("Added the variant `{}` to the union `{}`.", variant, new.name),
+        });
+    }
+    for (variant, _) in old_variants.iter().filter(|variant| !new_variants.contains(variant)) {
+        changes.push(Change {
+            item: new.name.clone(),
+            field: Some(variant.clone()),
+            kind: ChangeKind::VariantRemoved,
+            breaking: true,
+            description: format!("Removed the variant `{}` from the union `{}`.", variant, new.name),
+        });
+    }
+}
+
+/// What decides the Rust type of a field, leaving out the wording of the documentation.
+fn rust_type(field_type: &FieldType) -> (&str, usize) {
+    (&field_type.name, field_type.array_count)
+}
+
+/// The name as documented, e.g. `sendMessage` for methods.
+fn display_name(ty: &Type) -> String {
+    match ty.kind {
+        TypeKind::Method(_) => camel_case(&ty.name),
+        _ => ty.name.clone(),
+    }
+}
+
+fn kind_name(ty: &Type) -> &'static str {
+    match ty.kind {
+        TypeKind::Method(_) => "method",
+        _ => "type",
+    }
+}
+
+/// A changelog with the breaking changes first.
+pub fn markdown(changes: &[Change]) -> String {
+    if changes.is_empty() {
+        return "No changes.\n".to_owned();
+    }
+    let mut markdown = String::new();
+    for (breaking, heading) in &[(true, "Breaking changes"), (false, "Non-breaking changes")] {
+        let lines: Vec<_> = changes
+            .iter()
+            .filter(|change| change.breaking == *breaking)
+            .map(|change| format!("- {}\n", change.description))
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown.push_str(&format!("## {}\n\n", heading));
+        markdown.push_str(&lines.concat());
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::MODEL_VERSION;
+    use test_util::*;
+
+    fn model(types: Vec<Type>, unions: Vec<FieldType>) -> Model {
+        Model { version: MODEL_VERSION, types, unions }
+    }
+
+    fn options(non_exhaustive: bool) -> Options {
+        Options { non_exhaustive, ..Options::default() }
+    }
+
+    fn summary(changes: &[Change]) -> Vec<(String, bool)> {
+        changes.iter().map(|change| (change.description.clone(), change.breaking)).collect()
+    }
+
+    fn received(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|&name| name.to_owned()).collect()
+    }
+
+    #[test]
+    fn classifies_type_and_method_changes() {
+        let old = model(
+            vec![
+                ty("Chat", TypeKind::Type, vec![field("id", field_type("Integer")), field("title", optional("String"))]),
+                ty("SendDice", TypeKind::Method(field_type("Message")), vec![field("chat_id", field_type("Integer"))]),
+                ty("GetMe", TypeKind::Method(field_type("User")), vec![]),
+            ],
+            vec![],
+        );
+        let new = model(
+            vec![
+                ty("Chat", TypeKind::Type, vec![field("id", field_type("String")), field("title", field_type("String")), field("bio", optional("String"))]),
+                ty("SendDice", TypeKind::Method(field_type("MessageOrTrue")), vec![field("chat_id", field_type("Integer")), field("emoji", field_type("String"))]),
+                ty("Poll", TypeKind::Type, vec![]),
+            ],
+            vec![],
+        );
+        assert_eq!(summary(&diff(&old, &new, &received(&["Chat"]), &options(false))), vec![
+            ("`Chat.id` is now String instead of Integer.".to_owned(), true),
+            ("`Chat.title` is now required.".to_owned(), true),
+            ("Added the optional field `Chat.bio` (String).".to_owned(), false),
+            ("`sendDice` now returns MessageOrTrue instead of Message.".to_owned(), true),
+            ("Added the required parameter `sendDice.emoji` (String).".to_owned(), true),
+            ("Added the type `Poll`.".to_owned(), false),
+            ("Removed the method `getMe`.".to_owned(), true),
+        ]);
+    }
+
+    #[test]
+    fn new_required_fields_of_received_types_are_not_breaking() {
+        let old = model(vec![ty("Chat", TypeKind::Type, vec![field("id", field_type("Integer"))])], vec![]);
+        let new = model(vec![ty("Chat", TypeKind::Type, vec![field("id", field_type("Integer")), field("type", field_type("String"))])], vec![]);
+        assert_eq!(summary(&diff(&old, &new, &received(&["Chat"]), &options(false))), vec![
+            ("Added the required field `Chat.type` (String).".to_owned(), false),
+        ]);
+    }
+
+    #[test]
+    fn new_optional_fields_of_sent_types_change_their_new() {
+        let old = model(vec![ty("BotCommand", TypeKind::Type, vec![field("command", field_type("String"))])], vec![]);
+        let new = model(vec![ty("BotCommand", TypeKind::Type, vec![field("command", field_type("String")), field("description", optional("String"))])], vec![]);
+        assert_eq!(summary(&diff(&old, &new, &received(&[]), &options(false))), vec![
+            ("Added the optional field `BotCommand.description` (String).".to_owned(), true),
+        ]);
+    }
+
+    #[test]
+    fn new_variants_only_break_exhaustive_matches_on_received_unions() {
+        let old = model(vec![], vec![union("ChatMember", &["Owner"]), union("InputMedia", &["Photo"])]);
+        let new = model(vec![], vec![union("ChatMember", &["Owner", "Banned"]), union("InputMedia", &["Photo", "Video"])]);
+        let return_types = received(&["ChatMember"]);
+        assert_eq!(summary(&diff(&old, &new, &return_types, &options(false))), vec![
+            ("Added the variant `Banned` to the union `ChatMember`.".to_owned(), true),
+            ("Added the variant `Video` to the union `InputMedia`.".to_owned(), false),
+        ]);
+        assert!(diff(&old, &new, &return_types, &options(true)).iter().all(|change| !change.breaking));
+        assert!(diff(&new, &old, &return_types, &options(true)).iter().all(|change| change.breaking));
+    }
+
+    #[test]
+    fn markdown_lists_breaking_changes_first() {
+        let old = model(vec![ty("Chat", TypeKind::Type, vec![field("id", field_type("Integer"))])], vec![]);
+        let new = model(vec![ty("Chat", TypeKind::Type, vec![field("bio", optional("String"))])], vec![]);
+        let markdown = markdown(&diff(&old, &new, &received(&["Chat"]), &options(false)));
+        assert_eq!(
+            markdown,
+            "## Breaking changes\n\n- Removed the field `Chat.id`.\n\n\
+             ## Non-breaking changes\n\n- Added the optional field `Chat.bio` (String).\n"
+        );
+        assert_eq!(super::markdown(&[]), "No changes.\n");
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use types::*;
+use utils::doc_url;
+
+/// Documentation of a type, method, field or union, as the markup the HTML used.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Doc(pub Vec<Block>);
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Block {
+    Paragraph(Vec<Inline>),
+    List { ordered: bool, items: Vec<Vec<Inline>> },
+    Code(String),
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Inline {
+    Text(String),
+    Emphasis(Vec<Inline>),
+    Strong(Vec<Inline>),
+    Code(String),
+    Link { href: String, content: Vec<Inline> },
+    Break,
+}
+
+impl Doc {
+    /// Plain text of every block, one per line, as the generator reads it.
+    pub fn text(&self) -> String {
+        let blocks: Vec<_> = self.0.iter().map(Block::text).collect();
+        blocks.join("\n")
+    }
+
+    /// Markdown for rustdoc, with blocks separated by blank lines.
+    pub fn markdown(&self, links: &Links) -> String {
+        let blocks: Vec<_> = self.0.iter().map(|block| block.markdown(links)).collect();
+        blocks.join("\n\n")
+    }
+
+    /// Markdown of the documentation of the item `name`, ending with a link to its section.
+    pub fn item_markdown(&self, name: &str, links: &Links) -> String {
+        let link = format!("[Telegram docs]({})", doc_url(name));
+        if self.0.is_empty() {
+            link
+        } else {
+            format!("{}\n\n{}", self.markdown(links), link)
+        }
+    }
+}
+
+impl Block {
+    pub fn text(&self) -> String {
+        match self {
+            Block::Paragraph(inlines) => text(inlines),
+            Block::List { items, .. } => {
+                let items: Vec<_> = items.iter().map(|item| text(item)).collect();
+                items.join("\n")
+            }
+            Block::Code(code) => code.clone(),
+        }
+    }
+
+    fn markdown(&self, links: &Links) -> String {
+        match self {
+            Block::Paragraph(inlines) => markdown(inlines, links),
+            Block::List { ordered, items } => {
+                let items: Vec<_> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        if *ordered {
+                            format!("{}. {}", index + 1, markdown(item, links))
+                        } else {
+                            format!("- {}", markdown(item, links))
+                        }
+                    })
+                    .collect();
+                items.join("\n")
+            }
+            Block::Code(code) => format!("```text\n{}\n```", code.trim_end()),
+        }
+    }
+}
+
+pub fn text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) | Inline::Code(text) => text.clone(),
+            Inline::Emphasis(content) | Inline::Strong(content) | Inline::Link { content, .. } => text(content),
+            Inline::Break => String::new(),
+        })
+        .collect()
+}
+
+fn markdown(inlines: &[Inline], links: &Links) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape(text),
+            Inline::Emphasis(content) => format!("*{}*", markdown(content, links)),
+            Inline::Strong(content) => format!("**{}**", markdown(content, links)),
+            Inline::Code(code) if code.contains('`') => format!("`` {} ``", code),
+            Inline::Code(code) => format!("`{}`", code),
+            Inline::Link { href, content } => format!("[{}]({})", markdown(content, links), links.url(href)),
+            Inline::Break => "\\\n".to_owned(),
+        })
+        .collect()
+}
+
+/// Escapes the characters Markdown, or rustdoc's HTML, would read as markup.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if let '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' = character {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+/// Paths of the generated types and methods, by the anchor of their section in the documentation.
+#[derive(Debug, Default)]
+pub struct Links(HashMap<String, String>);
+
+impl Links {
+    /// Items behind a feature keep their URL, as the linking items may be compiled without them.
+    pub fn new(types: &[Type], unions: &[FieldType], is_gated: impl Fn(&str) -> bool) -> Self {
+        let mut paths = HashMap::new();
+        for ty in types.iter().filter(|ty| !is_gated(&ty.name)) {
+            let module = match ty.kind {
+                TypeKind::Method(_) => "methods",
+                _ => "types",
+            };
+            paths.insert(ty.name.to_lowercase(), format!("crate::{}::{}", module, ty.name));
+        }
+        for union in unions.iter().filter(|union| !is_gated(&union.name)) {
+            paths.insert(union.name.to_lowercase(), format!("crate::types::{}", union.name));
+        }
+        // documented as prose, but generated from a template
+        paths.insert("inputfile".to_owned(), "crate::types::InputFile".to_owned());
+        Links(paths)
+    }
+
+    /// Intra-doc link for anchors of generated items, absolute URL for anything else.
+    fn url(&self, href: &str) -> String {
+        let anchor = if href.starts_with('#') {
+            Some(&href[1..])
+        } else if href.starts_with("/bots/api#") {
+            Some(&href["/bots/api#".len()..])
+        } else {
+            None
+        };
+        match anchor.and_then(|anchor| self.0.get(anchor)) {
+            Some(path) => path.clone(),
+            None => absolute_url(href),
+        }
+    }
+}
+
+fn absolute_url(href: &str) -> String {
+    if href.starts_with('#') {
+        format!("https://core.telegram.org/bots/api{}", href)
+    } else if href.starts_with('/') {
+        format!("https://core.telegram.org{}", href)
+    } else {
+        href.to_owned()
+    }
+}
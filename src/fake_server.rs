@@ -0,0 +1,137 @@
+use client::client_module;
+use codegen::{Impl, Scope};
+use generator::{cfg_attributes, cfg_feature, field_name, push_impl, Context};
+use graph::Node;
+use std::collections::{HashMap, HashSet};
+use types::*;
+
+/// Generates the `fake_server` client module: a `Fake` value of every type, the documented
+/// parameter checks of every method, and the dispatch of requests to methods by name.
+pub fn generate_fake_server(
+    types: &[Type],
+    graph: &HashMap<&str, Node>,
+    modules: &mut HashSet<Module>,
+    context: &Context,
+) {
+    let mut scope = Scope::new();
+    let mut names: Vec<_> = graph.keys().collect();
+    names.sort();
+    for name in names {
+        let value = match &graph[name] {
+            Node::Union(variants) => {
+                let variant = variants
+                    .iter()
+                    .find(|(_, variant_type)| context.reference_features(name, variant_type).is_empty());
+                match variant {
+                    Some((variant_name, _)) => format!("{}::{}(Fake::fake())", name, variant_name),
+                    None => continue,
+                }
+            }
+            Node::Struct(_) => match types.iter().find(|ty| ty.name == *name) {
+                Some(ty) if ty.kind == TypeKind::Type => {
+                    let fields: Vec<_> = ty
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            let cfg = cfg_attributes(&context.field_features(name, field));
+                            format!("{}{}: Fake::fake()", cfg, field_name(&field.name))
+                        })
+                        .collect();
+                    format!("{} {{ {} }}", name, fields.join(", "))
+                }
+                _ => continue,
+            },
+        };
+        let mut fake_impl = Impl::new(name);
+        fake_impl.impl_trait("Fake").new_fn("fake").ret("Self").line(&value);
+        push_impl(&mut scope, &context.features(name), fake_impl);
+    }
+    let mut dispatch = Vec::new();
+    for ty in types {
+        if let TypeKind::Method(_) = ty.kind {
+            let mut validate_impl = Impl::new(&ty.name);
+            let validate = validate_impl
+                .impl_trait("Validate")
+                .new_fn("validate")
+                .arg_ref_self()
+                .ret("Result<(), String>");
+            for field in &ty.fields {
+                let (min, max, bytes) = match length_constraint(&field.doc.text()) {
+                    Some(constraint) if field.field_type.name == "String" && field.field_type.array_count == 0 => {
+                        constraint
+                    }
+                    _ => continue,
+                };
+                let name = field_name(&field.name);
+                let check = |value: &str| format!("check_length({:?}, {}, {}, {}, {})?;", field.name, value, min, max, bytes);
+                // one statement per field, as the cfg of a field only applies to the next one
+                for feature in context.field_features(&ty.name, field) {
+                    validate.line(&format!("#[{}]", cfg_feature(feature)));
+                }
+                if field.field_type.is_optional {
+                    validate
+                        .line(&format!("if let Some(value) = &self.{} {{", name))
+                        .line(&format!("    {}", check("value")))
+                        .line("}");
+                } else {
+                    validate.line(&check(&format!("&self.{}", name)));
+                }
+            }
+            validate.line("Ok(())");
+            push_impl(&mut scope, &context.features(&ty.name), validate_impl);
+            let call = if ty.name == "GetUpdates" {
+                "server.get_updates(params, files)".to_owned()
+            } else {
+                format!("server.call::<{}>(params, files)", ty.name)
+            };
+            for feature in context.features(&ty.name) {
+                dispatch.push(format!("    #[{}]", cfg_feature(feature)));
+            }
+            dispatch.push(format!("    {:?} => {},", ty.name.to_lowercase(), call));
+        }
+    }
+    {
+        let dispatch_fn = scope
+            .new_fn("dispatch")
+            .doc("Calls the method named `method`, ignoring case as the Bot API does.")
+            .arg("server", "&FakeServer")
+            .arg("method", "&str")
+            .arg("params", "serde_json::Map<String, serde_json::Value>")
+            .arg("files", "HashMap<String, Vec<u8>>")
+            .ret("Option<Result<serde_json::Value, ApiError>>")
+            .line("let result = match method.to_lowercase().as_str() {");
+        for line in &dispatch {
+            dispatch_fn.line(line);
+        }
+        dispatch_fn.line("    _ => return None,").line("};").line("Some(result)");
+    }
+    let mut contents = include_str!("../templates/client/fake_server.rs").to_owned();
+    contents.push('\n');
+    contents.push_str(&scope.to_string());
+    contents.push('\n');
+    contents.push_str(include_str!("../templates/client/fake_server_tests.rs"));
+    modules.insert(client_module("fake_server", "*", contents, Some("fake-server")));
+}
+
+/// Reads a length limit such as "1-4096 characters" or "0-64 bytes" from a parameter's
+/// documentation, as minimum, maximum and whether it counts bytes. Limits on "each" element of
+/// a list are left out.
+fn length_constraint(doc: &str) -> Option<(usize, usize, bool)> {
+    let words: Vec<_> = doc.split_whitespace().collect();
+    words.windows(2).enumerate().find_map(|(index, pair)| {
+        let mut range = pair[0].splitn(2, '-');
+        let min = range.next()?.parse().ok()?;
+        let max = range.next()?.parse().ok()?;
+        let bytes = if pair[1].starts_with("characters") {
+            false
+        } else if pair[1].starts_with("bytes") {
+            true
+        } else {
+            return None;
+        };
+        if words.get(index + 2).map_or(false, |word| word.starts_with("each")) {
+            return None;
+        }
+        Some((min, max, bytes))
+    })
+}
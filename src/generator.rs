@@ -1,71 +1,245 @@
-use codegen::{Field as CodegenField, Scope};
-use std::collections::HashSet;
+use changelog::version_feature_chain;
+use codegen::{Field as CodegenField, Impl, Scope};
+use doc::{Doc, Links};
+use options::Options;
+use schema::{struct_schema, union_schema};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use types::*;
+use unions::Union;
 use utils::*;
 
+pub struct Context {
+    pub return_types: HashSet<String>,
+    pub derives: HashMap<String, Vec<&'static str>>,
+    /// Types that may carry an `InputFile`, and so need to report their attachments.
+    pub uploads: HashSet<String>,
+    pub links: Links,
+    /// Feature of the types, methods and unions of the sections behind one, with `--sections`.
+    pub sections: HashMap<String, &'static str>,
+    /// Feature of the Bot API version of the types and methods added after `--api-baseline`.
+    pub versions: HashMap<String, String>,
+    /// Feature of the Bot API version of the fields added after `--api-baseline`, by the name of
+    /// their type or method and their own.
+    pub field_versions: HashMap<(String, String), String>,
+    /// Features of the required parameters of a method that it is not behind itself, which it
+    /// then needs as a whole, see `required_features`.
+    pub required_features: HashMap<String, Vec<String>>,
+    pub options: Options,
+}
+
+impl Context {
+    fn derives(&self, name: &str) -> &[&'static str] {
+        self.derives.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn section(&self, name: &str) -> Option<&'static str> {
+        self.sections.get(name).cloned()
+    }
+
+    /// Features of the generated code, each with the features it enables, for `Cargo.toml`.
+    pub fn cargo_features(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut features: BTreeMap<_, _> = self.sections.values().map(|&feature| (feature, Vec::new())).collect();
+        let versions = self.versions.values().chain(self.field_versions.values());
+        features.extend(version_feature_chain(versions.map(String::as_str)));
+        features
+    }
+
+    /// Features the type, method or union `name` is only compiled with.
+    pub fn features(&self, name: &str) -> Vec<&str> {
+        let section = self.section(name);
+        let version = self.versions.get(name).map(String::as_str);
+        let mut features: Vec<_> = section.into_iter().chain(version).collect();
+        for feature in self.required_features.get(name).into_iter().flatten() {
+            if !features.contains(&feature.as_str()) {
+                features.push(feature);
+            }
+        }
+        features
+    }
+
+    /// Features a reference from `owner` to `name` needs, besides the ones of `owner`.
+    pub fn reference_features(&self, owner: &str, name: &str) -> Vec<&str> {
+        let owner_features = self.features(owner);
+        let mut features = self.features(name);
+        features.retain(|feature| !owner_features.contains(feature));
+        features
+    }
+
+    /// Features the field `field` of `owner` needs, besides the ones of `owner`.
+    pub fn field_features(&self, owner: &str, field: &Field) -> Vec<&str> {
+        let mut features = self.reference_features(owner, &field.field_type.name);
+        if let Some(version) = self.field_versions.get(&(owner.to_owned(), field.name.clone())) {
+            if !self.features(owner).contains(&version.as_str()) && !features.contains(&version.as_str()) {
+                features.push(version);
+            }
+        }
+        features
+    }
+}
+
+/// Features of the methods with required parameters only compiled with features the method is
+/// not, which the whole method then needs, as it can't be built without them.
+pub fn required_features(types: &[Type], context: &Context) -> HashMap<String, Vec<String>> {
+    let mut required_features = HashMap::new();
+    for ty in types.iter().filter(|ty| match ty.kind {
+        TypeKind::Method(_) => true,
+        _ => false,
+    }) {
+        let mut features: Vec<String> = Vec::new();
+        for field in ty.fields.iter().filter(|field| !field.field_type.is_optional) {
+            for feature in context.field_features(&ty.name, field) {
+                if !features.iter().any(|other| other == feature) {
+                    features.push(feature.to_owned());
+                }
+            }
+        }
+        if !features.is_empty() {
+            required_features.insert(ty.name.clone(), features);
+        }
+    }
+    required_features
+}
+
 pub trait Generator {
     type ReturnType;
 
-    fn generate(self, modules: &mut HashSet<Module>, return_types: &HashSet<String>) -> Self::ReturnType;
+    fn generate(self, modules: &mut HashSet<Module>, context: &Context) -> Self::ReturnType;
 }
 
 impl Generator for Type {
     type ReturnType = String;
 
-    fn generate(self, modules: &mut HashSet<Module>, return_types: &HashSet<String>) -> Self::ReturnType {
+    fn generate(self, modules: &mut HashSet<Module>, context: &Context) -> Self::ReturnType {
         let mut scope = Scope::new();
         scope.import("crate::types", "*");
+        let schema = struct_schema(&self);
         if let TypeKind::Method(return_type) = self.kind.clone() {
-            let return_type = return_type.generate(modules, return_types);
-            let return_type_annotation = format!(r#"return_type = "{}""#, return_type);
-            let new_annotation = r#"new(vis = "pub")"#;
-            let set_annotation = r#"set(vis = "pub")"#;
-            let new_struct = scope
-                .new_struct(&self.name)
-                .doc(&self.docs.join("\n"))
-                .derive("Debug")
-                .derive("Serialize")
-                .derive("TelegramApi")
-                .derive("Setters")
-                .derive("New")
-                .annotation(vec![&return_type_annotation, new_annotation, set_annotation])
-                .vis("pub");
-            for field in self.fields {
-                new_struct.push_field(field.generate(modules, return_types));
+            let return_type = return_type.generate(modules, context);
+            let mut params = Vec::new();
+            let mut attachments = Vec::new();
+            let mut chat_id = None;
+            {
+                let new_struct = scope
+                    .new_struct(&self.name)
+                    .doc(&self.docs.item_markdown(&self.name, &context.links))
+                    .derive("Debug")
+                    .vis("pub");
+                for derive in context.derives(&self.name) {
+                    new_struct.derive(derive);
+                }
+                new_struct.derive("Serialize");
+                new_struct.push_annotation(DESERIALIZE_METHODS);
+                for field in self.fields {
+                    let features: Vec<_> = context.field_features(&self.name, &field).into_iter().map(ToOwned::to_owned).collect();
+                    if context.uploads.contains(&field.field_type.name) {
+                        attachments.push((field_name(&field.name).to_owned(), features.clone()));
+                    }
+                    if field.name == "chat_id" && field.field_type.name == "ChatIdOrUsername" {
+                        chat_id = Some(field.field_type.is_optional);
+                    }
+                    let mut param_type = field.field_type.clone();
+                    param_type.is_optional = false;
+                    params.push(BuilderParam {
+                        name: field_name(&field.name).to_owned(),
+                        field_type: param_type.generate(modules, context),
+                        is_optional: field.field_type.is_optional,
+                        features: features.clone(),
+                    });
+                    new_struct.push_field(generate_field(field, &features, modules, context));
+                }
+            }
+            let method_name = camel_case(&self.name);
+            {
+                let method_impl = scope
+                    .new_impl(&self.name)
+                    .impl_trait("Method")
+                    .associate_type("Response", &return_type);
+                method_impl
+                    .new_fn("name")
+                    .ret("&'static str")
+                    .line(&format!("{:?}", method_name));
+                if let Some(is_optional) = chat_id {
+                    let migrate_chat = method_impl
+                        .new_fn("migrate_chat")
+                        .arg_mut_self()
+                        .arg("migrate_to_chat_id", "Integer")
+                        .ret("bool");
+                    if is_optional {
+                        migrate_chat
+                            .line("if self.chat_id.is_none() {")
+                            .line("    return false;")
+                            .line("}")
+                            .line("self.chat_id = Some(migrate_to_chat_id.into());");
+                    } else {
+                        migrate_chat.line("self.chat_id = migrate_to_chat_id.into();");
+                    }
+                    migrate_chat.line("true");
+                }
             }
+            generate_attachments(&mut scope, &self.name, &attachments);
+            generate_builder(&mut scope, &self.name, &params);
+            modules.insert(method_module());
+            modules.insert(request_module());
+            modules.insert(response_module());
         } else {
-            let set_annotation = r#"set(vis = "pub")"#;
-            let get_annotation = r#"get(vis = "pub")"#;
-            let new_annotation = r#"new(vis = "pub")"#;
-            let new_struct = scope
-                .new_struct(&self.name)
-                .doc(&self.docs.join("\n"))
-                .derive("Debug")
-                .vis("pub");
+            let (serialize, deserialize) = serde_derives(&self.name, &context.return_types);
+            let mut accessors = Vec::new();
+            let mut attachments = Vec::new();
             {
-                let return_type = return_types.get(&self.name).is_some();
-                if return_type || self.name == "WebhookInfo" {
-                    new_struct.derive("Deserialize").derive("Clone").derive("Getters");
-                    new_struct.annotation(vec![get_annotation]);
+                let new_struct = scope
+                    .new_struct(&self.name)
+                    .doc(&self.docs.item_markdown(&self.name, &context.links))
+                    .derive("Debug")
+                    .vis("pub");
+                for derive in context.derives(&self.name) {
+                    new_struct.derive(derive);
+                }
+                if deserialize {
+                    new_struct.derive("Deserialize");
                 } else {
-                    new_struct.derive("Serialize").derive("Setters").derive("New");
-                    new_struct.annotation(vec![new_annotation, set_annotation]);
+                    new_struct.push_annotation(DESERIALIZE_METHODS);
                 }
-                if self.name == "MaskPosition" || self.name == "InlineKeyboardButton" || self.name == "InlineKeyboardMarkup" || self.name == "LoginUrl" {
-                    new_struct.derive("Serialize").derive("Setters").derive("New");
-                    new_struct.push_annotation(new_annotation).push_annotation(set_annotation);
+                if serialize {
+                    new_struct.derive("Serialize");
+                } else {
+                    new_struct.push_annotation(SERIALIZE_RESPONSES);
                 }
+                for field in self.fields {
+                    let features: Vec<_> = context.field_features(&self.name, &field).into_iter().map(ToOwned::to_owned).collect();
+                    if context.uploads.contains(&field.field_type.name) {
+                        attachments.push((field_name(&field.name).to_owned(), features.clone()));
+                    }
+                    accessors.push(Accessor {
+                        name: field_name(&field.name).to_owned(),
+                        field_type: field_rust_type(&field, modules, context),
+                        doc: field.doc.markdown(&context.links),
+                        features: features.clone(),
+                    });
+                    new_struct.push_field(generate_field(field, &features, modules, context));
+                }
+            }
+            if deserialize {
+                generate_getters(&mut scope, &self.name, &accessors);
             }
-            for field in self.fields {
-                new_struct.push_field(field.generate(modules, return_types));
+            if serialize {
+                generate_new(&mut scope, &self.name, &accessors);
+                generate_setters(&mut scope, &self.name, &accessors);
+            }
+            if context.uploads.contains(&self.name) {
+                generate_attachments(&mut scope, &self.name, &attachments);
             }
         }
-        let contents = scope.to_string();
+        scope.raw(&schema);
+        modules.insert(schema_module());
+        let section = context.section(&self.name);
+        let features = context.features(&self.name).into_iter().filter(|&feature| Some(feature) != section);
         let module = Module {
             kind: self.kind,
-            contents,
+            contents: scope.to_string(),
             module_name: snake_case(&self.name),
+            features: features.map(ToOwned::to_owned).collect(),
             module_type: self.name,
+            section: section.map(ToOwned::to_owned),
         };
         modules.insert(module);
         scope.to_string()
@@ -75,19 +249,10 @@ impl Generator for Type {
 impl Generator for Field {
     type ReturnType = CodegenField;
 
-    fn generate(mut self, modules: &mut HashSet<Module>, return_types: &HashSet<String>) -> Self::ReturnType {
+    fn generate(self, modules: &mut HashSet<Module>, context: &Context) -> Self::ReturnType {
         let is_optional = self.field_type.is_optional;
-        let field_type = match self.name.as_ref() {
-            "pinned_message" | "reply_to_message" => {
-                self.field_type.is_boxed = true;
-                self.field_type.generate(modules, return_types)
-            }
-            _ => self.field_type.generate(modules, return_types),
-        };
-        let field_name = match self.name.as_ref() {
-            "type" => "type_",
-            name => name,
-        };
+        let field_type = field_rust_type(&self, modules, context);
+        let field_name = field_name(&self.name);
         let mut field = CodegenField::new(field_name, &field_type);
         if field_name == "type_" {
             field.push_annotation(r#"serde(rename = "type")"#);
@@ -95,47 +260,149 @@ impl Generator for Field {
         if is_optional {
             field.push_annotation(r#"serde(skip_serializing_if = "Option::is_none")"#);
         }
-        field.doc(&self.doc);
+        field.doc(&self.doc.markdown(&context.links));
         field.vis("pub(crate)");
         field
     }
 }
 
-impl Generator for FieldType {
-    type ReturnType = String;
+/// The Rust type of `field`, boxed where a `Message` would otherwise contain itself.
+fn field_rust_type(field: &Field, modules: &mut HashSet<Module>, context: &Context) -> String {
+    let mut field_type = field.field_type.clone();
+    if let "pinned_message" | "reply_to_message" = field.name.as_ref() {
+        field_type.is_boxed = true;
+    }
+    field_type.generate(modules, context)
+}
 
-    fn generate(self, modules: &mut HashSet<Module>, return_types: &HashSet<String>) -> Self::ReturnType {
-        if let FieldKind::Enum(variants) = self.kind {
-            let mut scope = Scope::new();
-            {
-                scope.import("crate::types", "*");
-                let new_enum = scope
-                    .new_enum(&self.name)
-                    .derive("Debug")
-                    .vis("pub")
-                    .annotation(vec![r#"serde(untagged)"#]);
-                {
-                    let return_type = return_types.get(&self.name).is_some();
-                    if return_type {
-                        new_enum.derive("Deserialize").derive("Clone");
-                    } else {
-                        new_enum.derive("Serialize");
-                    }
-                }
-                for (variant_name, variant_type) in variants {
-                    let variant = new_enum.new_variant(&variant_name);
-                    variant.tuple(&variant_type);
+/// Whether the type or method `name` derives `Serialize` and `Deserialize`, outside of the fake
+/// server.
+pub fn serde_derives(name: &str, return_types: &HashSet<String>) -> (bool, bool) {
+    let deserialize = return_types.contains(name) || name == "WebhookInfo";
+    let serialize = !deserialize || name == "MaskPosition" || name == "InlineKeyboardButton" || name == "InlineKeyboardMarkup" || name == "LoginUrl";
+    (serialize, deserialize)
+}
+
+impl Generator for Union {
+    type ReturnType = ();
+
+    fn generate(self, modules: &mut HashSet<Module>, context: &Context) -> Self::ReturnType {
+        let (serialize, deserialize) = self.serde_derives(&context.return_types);
+        let has_fallback = self.has_fallback(&context.return_types, &context.options);
+        let FieldType { name, doc, kind, .. } = self.field_type;
+        let variants = match kind {
+            FieldKind::Enum(variants) => variants,
+            FieldKind::Simple => panic!("union {} has no variants", name),
+        };
+        let mut scope = Scope::new();
+        {
+            scope.import("crate::types", "*");
+            let new_enum = scope
+                .new_enum(&name)
+                .derive("Debug")
+                .vis("pub")
+                .annotation(vec![r#"serde(untagged)"#]);
+            if let Some(doc) = &doc {
+                new_enum.doc(&doc.item_markdown(&name, &context.links));
+            }
+            for derive in context.derives(&name) {
+                new_enum.derive(derive);
+            }
+            if serialize {
+                new_enum.derive("Serialize");
+            } else {
+                new_enum.push_annotation(SERIALIZE_RESPONSES);
+            }
+            if deserialize {
+                new_enum.derive("Deserialize");
+            } else {
+                new_enum.push_annotation(DESERIALIZE_METHODS);
+            }
+            for (variant_name, variant_type) in &variants {
+                let cfg = cfg_attributes(&context.reference_features(&name, variant_type));
+                let variant = new_enum.new_variant(&format!("{}{}", cfg, variant_name));
+                variant.tuple(variant_type);
+            }
+            if has_fallback {
+                new_enum.push_annotation("non_exhaustive");
+                new_enum.new_variant("Unknown").tuple("serde_json::Value");
+            }
+        }
+        if context.uploads.contains(&name) {
+            let attachments = scope
+                .new_impl(&name)
+                .impl_trait("Attachments")
+                .new_fn("attachments")
+                .arg_ref_self()
+                .arg("attachments", "&mut Vec<Attachment>")
+                .line("match self {");
+            for (variant_name, variant_type) in &variants {
+                if context.uploads.contains(variant_type) {
+                    attachments.line(&format!(
+                        "    {}{}::{}(value) => value.attachments(attachments),",
+                        cfg_attributes(&context.reference_features(&name, variant_type)),
+                        name,
+                        variant_name
+                    ));
                 }
             }
-            let contents = scope.to_string();
-            let module = Module {
-                kind: TypeKind::Enum,
-                contents,
-                module_name: snake_case(&self.name),
-                module_type: self.name.clone(),
-            };
-            modules.insert(module);
+            if has_fallback || variants.iter().any(|(_, variant_type)| !context.uploads.contains(variant_type)) {
+                attachments.line("    _ => {}");
+            }
+            attachments.line("}");
+        }
+        for (variant_name, variant_type) in &variants {
+            let is_unique = variants.iter().filter(|(_, other)| other == variant_type).count() == 1;
+            if !is_unique {
+                continue;
+            }
+            let variant = format!("{}::{}(value)", name, variant_name);
+            let mut from_impl = Impl::new(&name);
+            from_impl
+                .impl_trait(&format!("From<{}>", variant_type))
+                .new_fn("from")
+                .arg("value", variant_type)
+                .ret("Self")
+                .line(&variant);
+            push_impl(&mut scope, &context.reference_features(&name, variant_type), from_impl);
+            if variant_type == "String" {
+                scope
+                    .new_impl(&name)
+                    .generic("'a")
+                    .impl_trait("From<&'a str>")
+                    .new_fn("from")
+                    .arg("value", "&'a str")
+                    .ret("Self")
+                    .line(&format!("{}::{}(value.to_owned())", name, variant_name));
+            }
+        }
+        let doc_text = doc.as_ref().map(Doc::text);
+        scope.raw(&union_schema(&name, doc_text.as_ref().map(String::as_str), &variants));
+        let module = Module {
+            kind: TypeKind::Enum,
+            contents: scope.to_string(),
+            module_name: snake_case(&name),
+            module_type: name.clone(),
+            features: Vec::new(),
+            section: context.section(&name).map(ToOwned::to_owned),
         };
+        modules.insert(module);
+    }
+}
+
+impl Generator for FieldType {
+    type ReturnType = String;
+
+    fn generate(self, modules: &mut HashSet<Module>, _context: &Context) -> Self::ReturnType {
+        if self.name == "UnixTime" {
+            modules.insert(unix_time_module());
+        }
+        if self.name == "InputFile" {
+            modules.insert(input_file_module());
+        }
+        if self.name == "True" {
+            modules.insert(true_module());
+        }
         let mut field_type = self.name;
         field_type = match field_type.as_ref() {
             "Boolean" => "bool".to_string(),
@@ -159,8 +426,369 @@ impl Generator for FieldType {
     }
 }
 
+struct BuilderParam {
+    name: String,
+    field_type: String,
+    is_optional: bool,
+    /// Features of an optional parameter added after the baseline.
+    features: Vec<String>,
+}
+
+impl BuilderParam {
+    /// The `#[cfg]` attributes of the parameter, each followed by a space.
+    fn cfg(&self) -> String {
+        cfg_attributes(&self.features)
+    }
+}
+
+/// The `#[cfg]` attributes of code only compiled with `features`, each followed by a space.
+pub fn cfg_attributes<S: AsRef<str>>(features: &[S]) -> String {
+    features.iter().map(|feature| format!("#[{}] ", cfg_feature(feature.as_ref()))).collect()
+}
+
+/// Adds `item` to `scope`, only compiled with `features`; codegen has no attributes on impls.
+pub fn push_impl<S: AsRef<str>>(scope: &mut Scope, features: &[S], item: Impl) {
+    if features.is_empty() {
+        scope.push_impl(item);
+        return;
+    }
+    let mut item_scope = Scope::new();
+    item_scope.push_impl(item);
+    scope.raw(&format!("{}\n{}", cfg_attributes(features).trim_end(), item_scope.to_string()));
+}
+
+/// Groups `items` by the features they are only compiled with, in order of first appearance, to
+/// put each group in its own impl, as only whole impls can be compiled conditionally.
+pub fn group_by_features<T, F: PartialEq>(items: impl IntoIterator<Item = T>, features: impl Fn(&T) -> F) -> Vec<(F, Vec<T>)> {
+    let mut groups: Vec<(F, Vec<T>)> = Vec::new();
+    for item in items {
+        let item_features = features(&item);
+        match groups.iter_mut().find(|(group_features, _)| *group_features == item_features) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((item_features, vec![item])),
+        }
+    }
+    groups
+}
+
+/// Derives `Deserialize` for what clients only write with the `serde-methods` feature, to read
+/// method calls back, e.g. in the fake server.
+pub const DESERIALIZE_METHODS: &str = r#"cfg_attr(feature = "serde-methods", derive(Deserialize))"#;
+
+/// Derives `Serialize` for what clients only read, for the fake server to write responses.
+pub const SERIALIZE_RESPONSES: &str = r#"cfg_attr(feature = "fake-server", derive(Serialize))"#;
+
+pub fn field_name(name: &str) -> &str {
+    match name {
+        "type" => "type_",
+        name => name,
+    }
+}
+
+/// Generates `Name::builder()` and a `NameBuilder` whose type parameters track which required
+/// parameters were set, so `build` is only callable once all of them are.
+fn generate_builder(scope: &mut Scope, name: &str, params: &[BuilderParam]) {
+    let builder_name = format!("{}Builder", name);
+    let state = |param: &BuilderParam| {
+        let mut state = param.name.split('_').map(|word| {
+            let mut word = word.to_owned();
+            capitalize(&mut word);
+            word
+        }).collect::<String>();
+        state.push_str("Param");
+        state
+    };
+    let required: Vec<_> = params.iter().filter(|param| !param.is_optional).collect();
+    let unset_builder = if required.is_empty() {
+        builder_name.clone()
+    } else {
+        format!("{}<{}>", builder_name, required.iter().map(|_| "()").collect::<Vec<_>>().join(", "))
+    };
+    {
+        let builder_fn = scope
+            .new_impl(name)
+            .new_fn("builder")
+            .doc(&format!("Starts building a `{}`.", name))
+            .vis("pub")
+            .ret(&unset_builder)
+            .line(&format!("{} {{", builder_name));
+        for param in params {
+            let value = if param.is_optional { "None" } else { "()" };
+            builder_fn.line(&format!("    {}{}: {},", param.cfg(), param.name, value));
+        }
+        builder_fn.line("}");
+    }
+    {
+        let builder = scope
+            .new_struct(&builder_name)
+            .doc(&format!("Builder for [`{}`], created by `{}::builder()`.", name, name))
+            .derive("Debug")
+            .vis("pub");
+        for param in &required {
+            builder.generic(&state(param));
+        }
+        for param in params {
+            let field_type = if param.is_optional {
+                format!("Option<{}>", param.field_type)
+            } else {
+                state(param)
+            };
+            let mut field = CodegenField::new(&param.name, &field_type);
+            for feature in &param.features {
+                field.push_annotation(&cfg_feature(feature));
+            }
+            builder.push_field(field);
+        }
+    }
+    for param in &required {
+        let set_type = |param_type: &str| {
+            let states: Vec<_> = required
+                .iter()
+                .map(|other| if other.name == param.name { param_type.to_owned() } else { state(other) })
+                .collect();
+            format!("{}<{}>", builder_name, states.join(", "))
+        };
+        let builder_impl = scope.new_impl(&builder_name);
+        for other in required.iter().filter(|other| other.name != param.name) {
+            builder_impl.generic(&state(other));
+        }
+        for other in &required {
+            if other.name == param.name {
+                builder_impl.target_generic("()");
+            } else {
+                builder_impl.target_generic(&state(other));
+            }
+        }
+        let setter = builder_impl
+            .new_fn(&param.name)
+            .vis("pub")
+            .arg_self()
+            .arg(&param.name, &format!("impl Into<{}>", param.field_type))
+            .ret(&set_type(&param.field_type))
+            .line(&format!("{} {{", builder_name));
+        for other in params {
+            if other.name == param.name {
+                setter.line(&format!("    {}: {}.into(),", other.name, other.name));
+            } else {
+                setter.line(&format!("    {}{}: self.{},", other.cfg(), other.name, other.name));
+            }
+        }
+        setter.line("}");
+    }
+    let optional = params.iter().filter(|param| param.is_optional);
+    for (features, optional) in group_by_features(optional, |param| param.features.as_slice()) {
+        let mut builder_impl = Impl::new(&builder_name);
+        for param in &required {
+            builder_impl.generic(&state(param)).target_generic(&state(param));
+        }
+        for param in optional {
+            builder_impl
+                .new_fn(&param.name)
+                .vis("pub")
+                .arg_self()
+                .arg(&param.name, &format!("impl Into<{}>", param.field_type))
+                .ret("Self")
+                .line("Self {")
+                .line(&format!("    {}: Some({}.into()),", param.name, param.name))
+                .line("    ..self")
+                .line("}");
+        }
+        push_impl(scope, features, builder_impl);
+    }
+    let builder_impl = scope.new_impl(&builder_name);
+    for param in &required {
+        builder_impl.target_generic(&param.field_type);
+    }
+    let build = builder_impl
+        .new_fn("build")
+        .doc(&format!("Finishes building the `{}`.", name))
+        .vis("pub")
+        .arg_self()
+        .ret(name)
+        .line(&format!("{} {{", name));
+    for param in params {
+        build.line(&format!("    {}{}: self.{},", param.cfg(), param.name, param.name));
+    }
+    build.line("}");
+}
+
+/// A field of a type, as its constructor and accessors see it.
+struct Accessor {
+    name: String,
+    field_type: String,
+    doc: String,
+    /// Features of a field added after the baseline.
+    features: Vec<String>,
+}
+
+/// Generates `Name::new`, taking every field in order, for the types clients write.
+fn generate_new(scope: &mut Scope, name: &str, fields: &[Accessor]) {
+    let new_fn = scope
+        .new_impl(name)
+        .new_fn("new")
+        .doc("Creates the value from all of its fields, in the documented order.")
+        .vis("pub");
+    for field in fields {
+        new_fn.arg(&format!("{}{}", cfg_attributes(&field.features), field.name), &field.field_type);
+    }
+    new_fn.ret("Self").line("Self {");
+    for field in fields {
+        new_fn.line(&format!("    {}{},", cfg_attributes(&field.features), field.name));
+    }
+    new_fn.line("}");
+}
+
+/// Generates a `&` getter named after every field, for the types clients read.
+fn generate_getters(scope: &mut Scope, name: &str, fields: &[Accessor]) {
+    for (features, fields) in group_by_features(fields, |field| field.features.as_slice()) {
+        let mut getters = Impl::new(name);
+        for field in fields {
+            getters
+                .new_fn(&field.name)
+                .doc(&field.doc)
+                .vis("pub")
+                .arg_ref_self()
+                .ret(&format!("&{}", field.field_type))
+                .line(&format!("&self.{}", field.name));
+        }
+        push_impl(scope, features, getters);
+    }
+}
+
+/// Generates a `set_` setter for every field, for the types clients write.
+fn generate_setters(scope: &mut Scope, name: &str, fields: &[Accessor]) {
+    for (features, fields) in group_by_features(fields, |field| field.features.as_slice()) {
+        let mut setters = Impl::new(name);
+        for field in fields {
+            setters
+                .new_fn(&format!("set_{}", field.name.trim_end_matches('_')))
+                .doc(&format!("Sets `{}`.", field.name))
+                .vis("pub")
+                .arg_mut_self()
+                .arg(&field.name, &field.field_type)
+                .ret("&mut Self")
+                .line(&format!("self.{} = {};", field.name, field.name))
+                .line("self");
+        }
+        push_impl(scope, features, setters);
+    }
+}
+
+/// Implements `Attachments` by collecting from the given fields, which may carry files, each with
+/// the features it is only compiled with.
+fn generate_attachments(scope: &mut Scope, name: &str, fields: &[(String, Vec<String>)]) {
+    let attachments_impl = scope.new_impl(name).impl_trait("Attachments");
+    if fields.is_empty() {
+        return;
+    }
+    let attachments = attachments_impl
+        .new_fn("attachments")
+        .arg_ref_self()
+        .arg("attachments", "&mut Vec<Attachment>");
+    for (field, features) in fields {
+        for feature in features {
+            attachments.line(&format!("#[{}]", cfg_feature(feature)));
+        }
+        attachments.line(&format!("self.{}.attachments(attachments);", field));
+    }
+}
+
+/// A field only compiled with `features`.
+fn generate_field(field: Field, features: &[String], modules: &mut HashSet<Module>, context: &Context) -> CodegenField {
+    let mut field = field.generate(modules, context);
+    for feature in features {
+        field.push_annotation(&cfg_feature(feature));
+    }
+    field
+}
+
+fn request_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/request.rs").to_owned(),
+        module_name: "request".to_owned(),
+        module_type: "*".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+fn response_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/response.rs").to_owned(),
+        module_name: "response".to_owned(),
+        module_type: "*".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+fn method_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/method.rs").to_owned(),
+        module_name: "method".to_owned(),
+        module_type: "Method".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+fn input_file_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/input_file.rs").to_owned(),
+        module_name: "input_file".to_owned(),
+        module_type: "InputFile".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+fn schema_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/schema.rs").to_owned(),
+        module_name: "schema".to_owned(),
+        module_type: "*".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+fn true_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/true.rs").to_owned(),
+        module_name: "true_".to_owned(),
+        module_type: "True".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+fn unix_time_module() -> Module {
+    Module {
+        kind: TypeKind::Type,
+        contents: include_str!("../templates/unix_time.rs").to_owned(),
+        module_name: "unix_time".to_owned(),
+        module_type: "UnixTime".to_owned(),
+        features: Vec::new(),
+        section: None,
+    }
+}
+
+/// `cfg` of the code only compiled with `feature`.
+pub fn cfg_feature(feature: &str) -> String {
+    format!("cfg(feature = \"{}\")", feature)
+}
+
 pub fn generate_single_mod(module: &Module, string: &mut String) {
     string.insert_str(0, &format!("mod {};\n", &module.module_name));
+    let cfg: String = module.features.iter().map(|feature| format!("#[{}]\n", cfg_feature(feature))).collect();
+    string.insert_str(0, &cfg);
+    string.push_str(&cfg);
     let mut scope = Scope::new();
     scope
         .import(
@@ -170,3 +798,42 @@ pub fn generate_single_mod(module: &Module, string: &mut String) {
         .vis("pub");
     string.push_str(&scope.to_string());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::*;
+
+    #[test]
+    fn methods_need_the_features_of_their_required_parameters() {
+        let types = vec![
+            ty("Poll", TypeKind::Type, vec![field("id", field_type("String"))]),
+            ty("SendPoll", TypeKind::Method(field_type("Message")), vec![
+                field("chat_id", field_type("Integer")),
+                field("poll", field_type("Poll")),
+                field("reply_to", optional("Poll")),
+            ]),
+            ty("SendMessage", TypeKind::Method(field_type("Message")), vec![
+                field("text", field_type("String")),
+                field("entities", optional("String")),
+            ]),
+        ];
+        let mut context = Context {
+            return_types: HashSet::new(),
+            derives: HashMap::new(),
+            uploads: HashSet::new(),
+            links: Links::new(&types, &[], |_| false),
+            sections: HashMap::new(),
+            versions: vec![("Poll".to_owned(), "bot-api-4-2".to_owned())].into_iter().collect(),
+            field_versions: vec![(("SendMessage".to_owned(), "entities".to_owned()), "bot-api-4-1".to_owned())]
+                .into_iter()
+                .collect(),
+            required_features: HashMap::new(),
+            options: Options::default(),
+        };
+        context.required_features = required_features(&types, &context);
+        assert_eq!(context.features("SendPoll"), vec!["bot-api-4-2"]);
+        assert!(context.field_features("SendPoll", &types[1].fields[1]).is_empty());
+        assert!(context.features("SendMessage").is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::{HashMap, HashSet};
+use types::*;
+
+pub enum Node<'a> {
+    Struct(Vec<&'a FieldType>),
+    Union(&'a [(String, String)]),
+}
+
+impl<'a> Node<'a> {
+    pub fn references(&self) -> Vec<&'a str> {
+        match self {
+            Node::Struct(fields) => fields.iter().map(|field| field.name.as_str()).collect(),
+            Node::Union(variants) => variants.iter().map(|(_, variant_type)| variant_type.as_str()).collect(),
+        }
+    }
+}
+
+/// Every generated struct and union by name, with the type names it refers to.
+pub fn type_graph<'a>(types: &'a [Type], unions: &'a [FieldType]) -> HashMap<&'a str, Node<'a>> {
+    let mut nodes = HashMap::new();
+    for union in unions {
+        add_union(&mut nodes, union);
+    }
+    for ty in types {
+        if let TypeKind::Method(return_type) = &ty.kind {
+            add_union(&mut nodes, return_type);
+        }
+        for field in &ty.fields {
+            add_union(&mut nodes, &field.field_type);
+        }
+        let fields = ty.fields.iter().map(|field| &field.field_type).collect();
+        nodes.insert(ty.name.as_str(), Node::Struct(fields));
+    }
+    nodes
+}
+
+fn add_union<'a>(nodes: &mut HashMap<&'a str, Node<'a>>, field_type: &'a FieldType) {
+    if let FieldKind::Enum(variants) = &field_type.kind {
+        nodes.insert(field_type.name.as_str(), Node::Union(variants));
+    }
+}
+
+/// Names of every node that contains `leaf`, directly or through other nodes.
+pub fn containing(nodes: &HashMap<&str, Node>, leaf: &str) -> HashSet<String> {
+    let mut found = HashSet::new();
+    found.insert(leaf.to_owned());
+    loop {
+        let new: Vec<_> = nodes
+            .iter()
+            .filter(|(name, node)| {
+                !found.contains(**name) && node.references().iter().any(|name| found.contains(*name))
+            })
+            .map(|(name, _)| name.to_string())
+            .collect();
+        if new.is_empty() {
+            return found;
+        }
+        found.extend(new);
+    }
+}
@@ -1,52 +1,142 @@
 extern crate codegen;
 extern crate kuchiki;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+mod changelog;
+mod client;
 mod converter;
+mod coverage;
+mod derives;
+mod diff;
+mod doc;
+mod fake_server;
 mod generator;
+mod graph;
+mod model;
+mod options;
 mod parser;
+mod resolve;
+mod schema;
+mod sections;
+#[cfg(test)]
+mod test_util;
 mod types;
+mod unions;
 mod utils;
 mod writer;
 
-use generator::Generator;
-use kuchiki::traits::TendrilSink;
-use parser::{enum_parser, parser};
-use std::collections::HashSet;
-use std::{env, fs};
-use types::{Type, TypeKind};
-use writer::write_mod_files;
+use changelog::version_features;
+use client::{client_methods, generate_client, generate_method_registry};
+use derives::infer_derives;
+use doc::Links;
+use fake_server::generate_fake_server;
+use generator::{required_features, Context, Generator};
+use graph::{containing, type_graph};
+use model::Model;
+use options::{DiffArgs, Options};
+use sections::section_features;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::process;
+use types::TypeKind;
+use unions::intern_unions;
+use writer::{write_cargo_snippet, write_mod_files};
 
 fn main() {
-    let mut args = env::args().skip(1);
-    let telegram_html_file = args.next().unwrap();
-    let dir = args.next().unwrap();
-    let html = fs::read_to_string(telegram_html_file).unwrap();
-    let document = kuchiki::parse_html().one(html);
-
-    let parsed = parser(&document);
-    let converted: Vec<_> = parsed.map(Into::<Type>::into).collect();
-    let mut return_types: HashSet<_> = converted.iter().filter_map(|ty| {
-        if let TypeKind::Method(field) = &ty.kind {
-            Some(field.name.clone())
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("dump-model") {
+        args.next();
+        let input = args.next().unwrap();
+        let output = args.next().unwrap();
+        Model::load(&input).dump(&output);
+        return;
+    }
+    if args.peek().map(String::as_str) == Some("coverage") {
+        args.next();
+        let coverage = coverage::coverage(fs::read_to_string(args.next().unwrap()).unwrap());
+        print!("{}", coverage.report());
+        for warning in &coverage.warnings {
+            eprintln!("warning: {}", warning);
+        }
+        if args.next().as_ref().map(String::as_str) == Some("--strict") && !coverage.warnings.is_empty() {
+            process::exit(1);
+        }
+        return;
+    }
+    if args.peek().map(String::as_str) == Some("diff") {
+        args.next();
+        let diff_args = DiffArgs::from_args(args);
+        let old = Model::load(&diff_args.old);
+        let new = Model::load(&diff_args.new);
+        let changes = diff::diff(&old, &new, &new.return_types(), &diff_args.options);
+        if diff_args.json {
+            println!("{}", serde_json::to_string_pretty(&changes).unwrap());
         } else {
-            None
+            print!("{}", diff::markdown(&changes));
         }
-    }).collect();
-    converted.iter().for_each(|ty| {
-        if return_types.get(&ty.name).is_some() {
-            ty.fields.iter().for_each(|field| {
-                return_types.insert(field.field_type.name.clone());
-            });
-        };
+        return;
+    }
+    let input = args.next().unwrap();
+    let dir = args.next().unwrap();
+    let options = Options::from_args(args);
+    let model = Model::load(&input);
+    for problem in resolve::resolve(&model) {
+        eprintln!("warning: {}", problem);
+    }
+    let return_types = model.return_types();
+    let Model {
+        types: converted,
+        unions: enum_parsed,
+        ..
+    } = model;
+
+    let registry = generate_method_registry(&converted);
+    let unions = intern_unions(&converted, &enum_parsed);
+    let graph = type_graph(&converted, &enum_parsed);
+    let fallbacks = unions
+        .iter()
+        .filter(|union| union.has_fallback(&return_types, &options))
+        .map(|union| union.field_type.name.clone())
+        .collect();
+    let derives = infer_derives(&graph, &fallbacks);
+    let uploads = containing(&graph, "InputFile");
+    let sections = if options.sections {
+        section_features(&converted, &enum_parsed, &graph)
+    } else {
+        HashMap::new()
+    };
+    let (versions, field_versions) = match &options.api_baseline {
+        Some(baseline) => version_features(&converted, baseline),
+        None => (HashMap::new(), HashMap::new()),
+    };
+    let links = Links::new(&converted, &enum_parsed, |name| {
+        sections.contains_key(name) || versions.contains_key(name)
     });
-    let enum_parsed = enum_parser(&document);
+    let mut context = Context {
+        return_types,
+        derives,
+        uploads,
+        links,
+        sections,
+        versions,
+        field_versions,
+        required_features: HashMap::new(),
+        options,
+    };
+    context.required_features = required_features(&converted, &context);
 
     let mut modules = HashSet::new();
-    for i in converted {
-        i.generate(&mut modules, &return_types);
+    modules.insert(registry);
+    generate_client(client_methods(&converted), &mut modules, &context);
+    generate_fake_server(&converted, &graph, &mut modules, &context);
+    for union in unions {
+        union.generate(&mut modules, &context);
     }
-
-    for i in enum_parsed {
-        i.generate(&mut modules, &return_types);
+    for i in converted {
+        i.generate(&mut modules, &context);
     }
 
     let types = modules
@@ -64,5 +154,10 @@ fn main() {
         .filter(|module| module.kind == TypeKind::Enum);
     write_mod_files(&dir, types.peekable());
     write_mod_files(&dir, methods.peekable());
+    let client = modules
+        .iter()
+        .filter(|module| module.kind == TypeKind::Client);
     write_mod_files(&dir, enums.peekable());
+    write_mod_files(&dir, client.peekable());
+    write_cargo_snippet(&dir, &context.cargo_features());
 }
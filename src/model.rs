@@ -0,0 +1,99 @@
+//! The parsed API model, as the JSON intermediate schema written by `dump-model`.
+//!
+//! The document is an object with three keys:
+//!
+//! - `version`: `MODEL_VERSION`, bumped on every incompatible change of the format.
+//! - `types`: every type and method, each an object with a `name`, its `docs`, the anchor of its
+//!   `section`, the Bot API version that added it (`since`, or `null` when "Recent changes" doesn't
+//!   tell), its `fields` and a `kind`, either `"type"` or `{"method": <field type of the result>}`.
+//! - `unions`: the field types of the unions documented as lists of types.
+//!
+//! A field has a `name`, a `doc`, a `field_type` and a `since` like types. A field type has:
+//!
+//! - `name`: the Rust type, e.g. `UnixTime` or `ChatIdOrUsername`.
+//! - `telegram_type`: the type as the documentation writes it, e.g. `Integer or String`.
+//! - `array_count`: how many `Array of` wrap it.
+//! - `is_optional` and `is_boxed`.
+//! - `doc` and `section`: the description and section of a documented union, or `null`.
+//! - `kind`: `"simple"`, or `{"enum": [[<variant>, <type>], ...]}` for unions.
+//!
+//! Docs keep the markup of the documentation as a list of blocks: `{"paragraph": [<inline>, ...]}`,
+//! `{"list": {"ordered": <bool>, "items": [[<inline>, ...], ...]}}` or `{"code": <text>}`. An
+//! inline is `{"text": <text>}`, `{"code": <text>}`, `{"emphasis": [...]}`, `{"strong": [...]}`,
+//! `{"link": {"href": <url>, "content": [...]}}` or `"break"`.
+//!
+//! The generator reads such a document in place of the HTML when its input ends in `.json`, so
+//! the model can be patched by hand or produced by other tools.
+
+use kuchiki::traits::TendrilSink;
+use kuchiki::NodeRef;
+use changelog::apply_changelog;
+use parser::{changelog_parser, enum_parser, parser};
+use serde_json;
+use std::collections::HashSet;
+use std::fs;
+use types::*;
+
+pub const MODEL_VERSION: u32 = 4;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Model {
+    pub version: u32,
+    pub types: Vec<Type>,
+    pub unions: Vec<FieldType>,
+}
+
+impl Model {
+    pub fn from_html(html: String) -> Self {
+        Self::from_document(&kuchiki::parse_html().one(html))
+    }
+
+    pub fn from_document(document: &NodeRef) -> Self {
+        let mut types: Vec<_> = parser(document).map(Into::into).collect();
+        let releases: Vec<_> = changelog_parser(document).collect();
+        apply_changelog(&mut types, &releases);
+        Self {
+            version: MODEL_VERSION,
+            types,
+            unions: enum_parser(document).collect(),
+        }
+    }
+
+    /// Reads the model from `path`, either API documentation HTML or a model JSON document.
+    pub fn load(path: &str) -> Self {
+        let contents = fs::read_to_string(path).unwrap();
+        if !path.ends_with(".json") {
+            return Self::from_html(contents);
+        }
+        let model: Self = serde_json::from_str(&contents).unwrap();
+        if model.version != MODEL_VERSION {
+            panic!("model version {} is not supported, expected {}", model.version, MODEL_VERSION);
+        }
+        model
+    }
+
+    /// Types the Bot API sends: method results, the fields of those, and `ResponseParameters`.
+    pub fn return_types(&self) -> HashSet<String> {
+        let mut return_types: HashSet<_> = self.types.iter().filter_map(|ty| {
+            if let TypeKind::Method(field) = &ty.kind {
+                Some(field.name.clone())
+            } else {
+                None
+            }
+        }).collect();
+        // part of every response envelope, see ApiResponse
+        return_types.insert("ResponseParameters".to_owned());
+        self.types.iter().for_each(|ty| {
+            if return_types.get(&ty.name).is_some() {
+                ty.fields.iter().for_each(|field| {
+                    return_types.insert(field.field_type.name.clone());
+                });
+            };
+        });
+        return_types
+    }
+
+    pub fn dump(&self, path: &str) {
+        fs::write(path, serde_json::to_string_pretty(self).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+use changelog::parse_version;
+
+#[derive(Default)]
+pub struct Options {
+    pub non_exhaustive: bool,
+    /// Put the sections most bots don't need behind cargo features, e.g. `stickers`.
+    pub sections: bool,
+    /// Put what Bot API versions after this one added behind cargo features, e.g. `bot-api-4-2`.
+    pub api_baseline: Option<String>,
+}
+
+impl Options {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--non-exhaustive" => options.non_exhaustive = true,
+                "--sections" => options.sections = true,
+                "--api-baseline" => {
+                    let baseline = args.next().expect("--api-baseline needs a version");
+                    if parse_version(&baseline).is_none() {
+                        panic!("--api-baseline needs a Bot API version such as 4.2, not {:?}", baseline);
+                    }
+                    options.api_baseline = Some(baseline);
+                }
+                _ => panic!("unknown option {}", arg),
+            }
+        }
+        options
+    }
+}
+
+/// Arguments of `diff <old> <new> [--json]`, with the options the crate is generated with, which
+/// decide what breaks it.
+pub struct DiffArgs {
+    pub old: String,
+    pub new: String,
+    /// Print the changes as JSON instead of a Markdown changelog.
+    pub json: bool,
+    pub options: Options,
+}
+
+impl DiffArgs {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let usage = "usage: diff <old model> <new model> [--json] [generator options]";
+        let mut paths = Vec::new();
+        let mut json = false;
+        let mut options = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => json = true,
+                "--api-baseline" => {
+                    options.push(arg);
+                    options.extend(args.next());
+                }
+                _ if arg.starts_with("--") => options.push(arg),
+                _ => paths.push(arg),
+            }
+        }
+        if paths.len() != 2 {
+            panic!("{}", usage);
+        }
+        let new = paths.pop().unwrap();
+        let old = paths.pop().unwrap();
+        Self {
+            old,
+            new,
+            json,
+            options: Options::from_args(options.into_iter()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff_args(args: &[&str]) -> DiffArgs {
+        DiffArgs::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn diff_takes_json_and_options_anywhere() {
+        let args = diff_args(&["--json", "old.json", "--non-exhaustive", "new.html"]);
+        assert_eq!((args.old.as_str(), args.new.as_str(), args.json), ("old.json", "new.html", true));
+        assert!(args.options.non_exhaustive);
+        let args = diff_args(&["old.json", "new.json", "--api-baseline", "4.2"]);
+        assert!(!args.json);
+        assert_eq!(args.options.api_baseline, Some("4.2".to_owned()));
+    }
+
+    #[test]
+    #[should_panic(expected = "usage: diff <old model> <new model>")]
+    fn diff_needs_two_models() {
+        diff_args(&["old.json", "--json"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "--api-baseline needs a Bot API version such as 4.2")]
+    fn baseline_is_a_version() {
+        Options::from_args(vec!["--api-baseline".to_owned(), "latest".to_owned()].into_iter());
+    }
+}
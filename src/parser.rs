@@ -1,3 +1,4 @@
+use doc::{text, Block, Doc, Inline};
 use kuchiki::{
     iter::NodeIterator,
     {ElementData, NodeDataRef, NodeRef},
@@ -7,20 +8,50 @@ use types::*;
 trait Parse {
     fn parse(node: &NodeRef) -> Self;
 
-    fn parse_name_and_docs(table_siblings: impl Iterator<Item = NodeRef>) -> (String, Vec<String>) {
+    fn parse_name_and_docs(table_siblings: impl Iterator<Item = NodeRef>) -> (String, Doc) {
         let mut docs = Vec::new();
         let nodes = table_siblings.skip(1).step_by(2);
         for node in nodes {
             let element = node.as_element().unwrap();
             let name = element.name.local.to_string();
             match name.as_str() {
-                "h4" => return (Self::parse_name(&node), docs),
-                _ => docs.insert(0, Self::parse_doc(&node)),
+                "h4" => return (Self::parse_name(&node), Doc(docs)),
+                _ => {
+                    let blocks = Self::parse_blocks(&node);
+                    docs.splice(0..0, blocks);
+                }
             }
         }
         panic!("cannot parse name and docs");
     }
 
+    /// Notes that follow the table of a type or method, up to the next section.
+    fn parse_trailing_docs(table_siblings: impl Iterator<Item = NodeRef>) -> Vec<Block> {
+        table_siblings
+            .filter(|node| node.as_element().is_some())
+            .take_while(|node| match node.as_element().unwrap().name.local.as_ref() {
+                "h3" | "h4" | "hr" | "table" => false,
+                _ => true,
+            })
+            .flat_map(|node| Self::parse_blocks(&node))
+            .collect()
+    }
+
+    /// Anchor of the section, the last h3 before the node.
+    fn parse_section(node: &NodeRef) -> String {
+        for sibling in node.preceding_siblings() {
+            match sibling.as_element() {
+                Some(element) if &element.name.local == "h3" => {
+                    let anchor = sibling.select_first("a").unwrap();
+                    let attributes = anchor.attributes.borrow();
+                    return attributes.get("name").unwrap().to_owned();
+                }
+                _ => {}
+            }
+        }
+        panic!("cannot parse section");
+    }
+
     fn parse_name(node: &NodeRef) -> String {
         let child = node.last_child().unwrap();
         match child.as_text() {
@@ -29,12 +60,56 @@ trait Parse {
         }
     }
 
-    fn parse_doc(node: &NodeRef) -> String {
-        let nodes = node.children();
-        nodes
-            .map(|node| match node.as_text() {
-                Some(text) => text.borrow().to_owned(),
-                None => Self::parse_doc(&node),
+    fn parse_doc(node: &NodeRef) -> Doc {
+        Doc(Self::parse_blocks(node))
+    }
+
+    fn parse_blocks(node: &NodeRef) -> Vec<Block> {
+        let name = match node.as_element() {
+            Some(element) => element.name.local.to_string(),
+            None => return Vec::new(),
+        };
+        match name.as_str() {
+            "blockquote" | "div" => node.children().flat_map(|child| Self::parse_blocks(&child)).collect(),
+            "ul" | "ol" => {
+                let items = node
+                    .children()
+                    .filter(|child| child.as_element().is_some())
+                    .map(|child| Self::parse_inlines(&child))
+                    .collect();
+                vec![Block::List {
+                    ordered: name == "ol",
+                    items,
+                }]
+            }
+            "pre" => vec![Block::Code(node.text_contents())],
+            _ => paragraph(Self::parse_inlines(node)),
+        }
+    }
+
+    fn parse_inlines(node: &NodeRef) -> Vec<Inline> {
+        node.children()
+            .flat_map(|child| {
+                if let Some(text) = child.as_text() {
+                    return vec![Inline::Text(text.borrow().to_owned())];
+                }
+                let href = match child.as_element() {
+                    Some(element) => element.attributes.borrow().get("href").map(ToOwned::to_owned),
+                    None => return Vec::new(),
+                };
+                let name = child.as_element().unwrap().name.local.to_string();
+                let content = Self::parse_inlines(&child);
+                match name.as_str() {
+                    "em" | "i" => vec![Inline::Emphasis(content)],
+                    "strong" | "b" => vec![Inline::Strong(content)],
+                    "code" => vec![Inline::Code(child.text_contents())],
+                    "br" => vec![Inline::Break],
+                    "a" => match href {
+                        Some(href) => vec![Inline::Link { href, content }],
+                        None => content,
+                    },
+                    _ => content,
+                }
             })
             .collect()
     }
@@ -81,35 +156,32 @@ impl Parse for TelegramTypeOrMethod {
 
 impl Parse for TelegramType {
     fn parse(table: &NodeRef) -> Self {
-        let (name, docs) = Self::parse_name_and_docs(table.preceding_siblings());
+        let (name, mut docs) = Self::parse_name_and_docs(table.preceding_siblings());
+        docs.0.extend(Self::parse_trailing_docs(table.following_siblings()));
+        let section = Self::parse_section(table);
         let fields = Self::parse_fields(table.select("tr").unwrap());
-        Self { name, docs, fields }
+        Self {
+            name,
+            docs,
+            section,
+            fields,
+        }
     }
 
     fn parse_fields(trs: impl Iterator<Item = NodeDataRef<ElementData>>) -> Vec<TelegramField> {
         trs.skip(1)
             .map(|tr| {
                 let tr = tr.as_node();
-                let tds = tr.children().select("td").unwrap().enumerate();
-                let field: Vec<String> = tds
-                    .map(|(i, td)| match i {
-                        0 => Self::parse_field(&td.as_node()),
-                        1 => Self::parse_type(&td.as_node()),
-                        2 => Self::parse_doc(&td.as_node()),
-                        _ => panic!("no field, type, doc or required field"),
-                    })
-                    .collect();
-                let doc = &field[2];
-                let is_optional = doc.starts_with("Optional. ");
-                let doc = if is_optional {
-                    doc.replace("Optional. ", "")
-                } else {
-                    doc.to_owned()
-                };
+                let tds: Vec<_> = tr.children().select("td").unwrap().collect();
+                if tds.len() != 3 {
+                    panic!("no field, type, doc or required field");
+                }
+                let mut doc = Self::parse_doc(tds[2].as_node());
+                let is_optional = strip_optional(&mut doc);
                 TelegramField {
-                    name: field[0].clone(),
+                    name: Self::parse_field(tds[0].as_node()),
                     telegram_type: TelegramFieldType {
-                        name: field[1].clone(),
+                        name: Self::parse_type(tds[1].as_node()),
                         is_optional,
                     },
                     doc,
@@ -121,9 +193,11 @@ impl Parse for TelegramType {
 
 impl Parse for TelegramMethod {
     fn parse(table: &NodeRef) -> Self {
-        let (name, docs) = Self::parse_name_and_docs(table.preceding_siblings());
+        let (name, mut docs) = Self::parse_name_and_docs(table.preceding_siblings());
+        let return_type = Self::parse_return_type(&docs.0[0].text());
+        docs.0.extend(Self::parse_trailing_docs(table.following_siblings()));
+        let section = Self::parse_section(table);
         let fields = Self::parse_fields(table.select("tr").unwrap());
-        let return_type = Self::parse_return_type(&docs[0]);
         let return_type = TelegramFieldType {
             name: return_type,
             is_optional: false,
@@ -131,6 +205,7 @@ impl Parse for TelegramMethod {
         Self {
             name,
             docs,
+            section,
             fields,
             return_type,
         }
@@ -140,23 +215,17 @@ impl Parse for TelegramMethod {
         trs.skip(1)
             .map(|tr| {
                 let tr = tr.as_node();
-                let tds = tr.children().select("td").unwrap().enumerate();
-                let field: Vec<String> = tds
-                    .map(|(i, td)| match i {
-                        0 => Self::parse_field(&td.as_node()),
-                        1 => Self::parse_type(&td.as_node()),
-                        2 => Self::parse_required(&td.as_node()),
-                        3 => Self::parse_doc(&td.as_node()),
-                        _ => panic!("no field, type, doc or required field"),
-                    })
-                    .collect();
+                let tds: Vec<_> = tr.children().select("td").unwrap().collect();
+                if tds.len() != 4 {
+                    panic!("no field, type, doc or required field");
+                }
                 TelegramField {
-                    name: field[0].clone(),
+                    name: Self::parse_field(tds[0].as_node()),
                     telegram_type: TelegramFieldType {
-                        name: field[1].clone(),
-                        is_optional: "Optional" == field[2],
+                        name: Self::parse_type(tds[1].as_node()),
+                        is_optional: "Optional" == Self::parse_required(tds[2].as_node()),
                     },
-                    doc: field[3].clone(),
+                    doc: Self::parse_doc(tds[3].as_node()),
                 }
             })
             .collect()
@@ -267,8 +336,10 @@ impl Parse for FieldType {
             .map(|a| (a.clone(), a))
             .collect();
         Self {
+            telegram_type: name.clone(),
             name,
             doc: Some(doc),
+            section: Some(Self::parse_section(node)),
             array_count: 0,
             is_optional: false,
             kind: FieldKind::Enum(variants),
@@ -277,6 +348,28 @@ impl Parse for FieldType {
     }
 }
 
+impl Parse for TelegramRelease {
+    fn parse(p: &NodeRef) -> Self {
+        let version = p.text_contents().trim_start_matches("Bot API ").trim_end_matches('.').to_owned();
+        let changes = p
+            .following_siblings()
+            .filter(|node| node.as_element().is_some())
+            .take_while(|node| match node.as_element().unwrap().name.local.as_ref() {
+                "h3" | "h4" => false,
+                _ => true,
+            })
+            .filter(|node| &node.as_element().unwrap().name.local == "ul")
+            .flat_map(|ul| {
+                ul.children()
+                    .filter(|li| li.as_element().is_some())
+                    .map(|li| Self::parse_inlines(&li))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Self { version, changes }
+    }
+}
+
 pub fn parser(document: &NodeRef) -> impl Iterator<Item = TelegramTypeOrMethod> {
     let css_selector = "h4 + p ~ table";
     document.select(css_selector).unwrap().map(|table| {
@@ -292,3 +385,99 @@ pub fn enum_parser(document: &NodeRef) -> impl Iterator<Item = FieldType> {
         FieldType::parse(ul)
     })
 }
+
+/// The releases of "Recent changes", newest first.
+pub fn changelog_parser(document: &NodeRef) -> impl Iterator<Item = TelegramRelease> {
+    let css_selector = "h4 + p";
+    document
+        .select(css_selector)
+        .unwrap()
+        .filter(|p| p.text_contents().starts_with("Bot API "))
+        .map(|p| TelegramRelease::parse(p.as_node()))
+}
+
+/// Every h3 and h4 heading, in the order of the documentation.
+pub fn heading_parser(document: &NodeRef) -> impl Iterator<Item = TelegramHeading> {
+    let css_selector = "h3, h4";
+    document.select(css_selector).unwrap().map(|heading| {
+        let level = if &heading.name.local == "h3" { 3 } else { 4 };
+        let anchor = heading.as_node().select_first("a").unwrap();
+        let anchor = anchor.attributes.borrow().get("name").unwrap().to_owned();
+        let text = heading.text_contents().trim().to_owned();
+        TelegramHeading { level, anchor, text }
+    })
+}
+
+/// A paragraph, or a paragraph followed by a list when its lines after `<br>` are numbered by
+/// `<strong>1.</strong>`, as in the "Notes" of the documentation.
+fn paragraph(inlines: Vec<Inline>) -> Vec<Block> {
+    let mut lines = vec![Vec::new()];
+    for inline in inlines {
+        match inline {
+            Inline::Break => lines.push(Vec::new()),
+            inline => lines.last_mut().unwrap().push(inline),
+        }
+    }
+    let is_numbered = |line: &[Inline]| match line.first() {
+        Some(Inline::Strong(content)) => {
+            let number = text(content);
+            number.len() > 1 && number.ends_with('.') && number[..number.len() - 1].bytes().all(|byte| byte.is_ascii_digit())
+        }
+        _ => false,
+    };
+    let first_item = lines.iter().position(|line| is_numbered(line)).unwrap_or_else(|| lines.len());
+    // an empty line, from two `<br>` in a row, ends a paragraph
+    let mut blocks: Vec<_> = lines[..first_item]
+        .split(|line| line.is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| Block::Paragraph(join_lines(paragraph)))
+        .collect();
+    let mut items: Vec<Vec<Inline>> = Vec::new();
+    for line in lines.into_iter().skip(first_item) {
+        if is_numbered(&line) {
+            let mut item: Vec<_> = line.into_iter().skip(1).collect();
+            if let Some(Inline::Text(text)) = item.first_mut() {
+                *text = text.trim_start().to_owned();
+            }
+            items.push(item);
+        } else {
+            let item = items.last_mut().unwrap();
+            item.push(Inline::Break);
+            item.extend(line);
+        }
+    }
+    if !items.is_empty() {
+        blocks.push(Block::List { ordered: true, items });
+    }
+    blocks
+}
+
+fn join_lines(lines: &[Vec<Inline>]) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if index != 0 {
+            inlines.push(Inline::Break);
+        }
+        inlines.extend(line.iter().cloned());
+    }
+    inlines
+}
+
+/// Removes the leading "*Optional*. " of the description of an optional field.
+fn strip_optional(doc: &mut Doc) -> bool {
+    let inlines = match doc.0.first_mut() {
+        Some(Block::Paragraph(inlines)) => inlines,
+        _ => return false,
+    };
+    let is_optional = match inlines.as_slice() {
+        [Inline::Emphasis(content), Inline::Text(rest), ..] => text(content) == "Optional" && rest.starts_with(". "),
+        _ => false,
+    };
+    if is_optional {
+        inlines.remove(0);
+        if let Inline::Text(rest) = &mut inlines[0] {
+            *rest = rest[2..].to_owned();
+        }
+    }
+    is_optional
+}
@@ -0,0 +1,125 @@
+//! Checks that every type a field, result or union variant names is generated or built in, so
+//! broken names show up before compiling the output.
+
+use model::Model;
+use std::collections::HashMap;
+use types::*;
+
+/// Built into Rust or the prelude of the generated crate, or written by the generator itself.
+const PRIMITIVES: &[&str] = &[
+    "Integer",
+    "Int",
+    "Float",
+    "Float number",
+    "String",
+    "Boolean",
+    "True",
+    "UnixTime",
+    "InputFile",
+];
+
+/// Every unresolved reference, with the path of the field, result or variant naming it, and
+/// every name defined twice.
+pub fn resolve(model: &Model) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut symbols: HashMap<&str, String> = HashMap::new();
+    let mut methods: HashMap<&str, String> = HashMap::new();
+    for primitive in PRIMITIVES {
+        define(&mut symbols, &mut problems, primitive, "a primitive".to_owned());
+    }
+    for ty in &model.types {
+        match ty.kind {
+            TypeKind::Method(_) => define(&mut methods, &mut problems, &ty.name, format!("the method `{}`", ty.name)),
+            _ => define(&mut symbols, &mut problems, &ty.name, format!("the type `{}`", ty.name)),
+        }
+    }
+    for union in &model.unions {
+        define(&mut symbols, &mut problems, &union.name, format!("the union `{}`", union.name));
+    }
+
+    let mut references = Vec::new();
+    for ty in &model.types {
+        if let TypeKind::Method(result) = &ty.kind {
+            references.push((format!("{} (result)", ty.name), result));
+        }
+        for field in &ty.fields {
+            references.push((format!("{}.{}", ty.name, field.name), &field.field_type));
+        }
+    }
+    for union in &model.unions {
+        references.push((union.name.clone(), union));
+    }
+    // unions written as "A or B" are generated where they are used, once per name
+    for (_, field_type) in &references {
+        if let FieldKind::Enum(_) = field_type.kind {
+            if !model.unions.iter().any(|union| union.name == field_type.name) {
+                symbols.entry(&field_type.name).or_insert_with(|| format!("the union `{}`", field_type.name));
+            }
+        }
+    }
+    for (path, field_type) in &references {
+        if !symbols.contains_key(field_type.name.as_str()) {
+            problems.push(format!("`{}` names `{}`, which is not defined", path, field_type.name));
+        }
+        if let FieldKind::Enum(variants) = &field_type.kind {
+            for (variant, variant_type) in variants {
+                if !symbols.contains_key(variant_type.as_str()) {
+                    problems.push(format!(
+                        "`{}::{}` names `{}`, which is not defined",
+                        field_type.name, variant, variant_type
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
+
+fn define<'a>(symbols: &mut HashMap<&'a str, String>, problems: &mut Vec<String>, name: &'a str, definition: String) {
+    if let Some(previous) = symbols.insert(name, definition.clone()) {
+        problems.push(format!("`{}` is defined by both {} and {}", name, previous, definition));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::MODEL_VERSION;
+    use test_util::*;
+
+    #[test]
+    fn resolves_types_primitives_and_unions() {
+        let model = Model {
+            version: MODEL_VERSION,
+            types: vec![
+                ty("User", TypeKind::Type, vec![field("id", field_type("Integer")), field("photo", field_type("InputFile"))]),
+                ty("SendMessage", TypeKind::Method(union("MessageOrTrue", &["User", "True"])), vec![
+                    field("chat_id", union("IntegerOrString", &["Integer", "String"])),
+                    field("reply_markup", field_type("ReplyMarkup")),
+                ]),
+            ],
+            unions: vec![union("ReplyMarkup", &["User"])],
+        };
+        assert!(resolve(&model).is_empty(), "{:?}", resolve(&model));
+    }
+
+    #[test]
+    fn reports_dangling_references_and_duplicates() {
+        let model = Model {
+            version: MODEL_VERSION,
+            types: vec![
+                ty("InlineKeyboardButton", TypeKind::Type, vec![field("callback_game", field_type("CallbackGame"))]),
+                ty("Message", TypeKind::Type, vec![]),
+                ty("SendGame", TypeKind::Method(union("GameOrTrue", &["Game", "True"])), vec![]),
+                ty("Message", TypeKind::Type, vec![]),
+            ],
+            unions: vec![union("String", &["Message"])],
+        };
+        assert_eq!(resolve(&model), vec![
+            "`Message` is defined by both the type `Message` and the type `Message`",
+            "`String` is defined by both a primitive and the union `String`",
+            "`InlineKeyboardButton.callback_game` names `CallbackGame`, which is not defined",
+            "`GameOrTrue::Game` names `Game`, which is not defined",
+        ]);
+    }
+}
@@ -0,0 +1,53 @@
+use types::*;
+use utils::doc_url;
+
+/// The `SCHEMA` of a struct, describing its fields.
+pub fn struct_schema(ty: &Type) -> String {
+    let mut fields = String::new();
+    for field in &ty.fields {
+        fields.push_str("        FieldInfo {\n");
+        fields.push_str(&format!("            name: {:?},\n", field.name));
+        fields.push_str(&format!("            telegram_type: {:?},\n", field.field_type.telegram_type));
+        fields.push_str(&format!("            optional: {},\n", field.field_type.is_optional));
+        fields.push_str(&format!("            doc: {:?},\n", field.doc.text()));
+        fields.push_str("        },\n");
+    }
+    let doc_url = format!("Some({:?})", doc_url(&ty.name));
+    schema(&ty.name, &ty.docs.text(), &doc_url, &format!("Shape::Struct(&[\n{}    ])", fields))
+}
+
+/// The `SCHEMA` of a union, describing its variants.
+pub fn union_schema(name: &str, doc: Option<&str>, variants: &[(String, String)]) -> String {
+    let mut variant_infos = String::new();
+    for (variant_name, variant_type) in variants {
+        variant_infos.push_str("        VariantInfo {\n");
+        variant_infos.push_str(&format!("            name: {:?},\n", variant_name));
+        variant_infos.push_str(&format!("            telegram_type: {:?},\n", variant_type));
+        variant_infos.push_str("        },\n");
+    }
+    // only unions with a documentation section of their own have a doc
+    let doc_url = match doc {
+        Some(_) => format!("Some({:?})", doc_url(name)),
+        None => "None".to_owned(),
+    };
+    let shape = format!("Shape::Union(&[\n{}    ])", variant_infos);
+    schema(name, doc.unwrap_or_default(), &doc_url, &shape)
+}
+
+fn schema(name: &str, doc: &str, doc_url: &str, shape: &str) -> String {
+    format!(
+        "impl {name} {{\n    \
+         /// Description of `{name}` from the documentation.\n    \
+         pub const SCHEMA: &'static TypeInfo = &TypeInfo {{\n        \
+         name: {name:?},\n        \
+         doc: {doc:?},\n        \
+         doc_url: {doc_url},\n        \
+         shape: {shape},\n    \
+         }};\n\
+         }}",
+        name = name,
+        doc = doc,
+        doc_url = doc_url,
+        shape = shape.replace('\n', "\n    "),
+    )
+}
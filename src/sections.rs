@@ -0,0 +1,85 @@
+use graph::Node;
+use std::collections::HashMap;
+use types::*;
+
+/// Cargo feature, and module, of the items of a section of the documentation. The sections every
+/// bot needs have none.
+pub fn section_feature(section: &str) -> Option<&'static str> {
+    match section {
+        "stickers" => Some("stickers"),
+        "inline-mode" => Some("inline"),
+        "payments" => Some("payments"),
+        "telegram-passport" => Some("passport"),
+        "games" => Some("games"),
+        _ => None,
+    }
+}
+
+/// The feature of every type, method and union only compiled with one. A union not documented
+/// on its own is in the section of its variants; one mixing sections is always compiled, with
+/// each variant behind the feature of its own.
+pub fn section_features(
+    types: &[Type],
+    unions: &[FieldType],
+    graph: &HashMap<&str, Node>,
+) -> HashMap<String, &'static str> {
+    let mut features = HashMap::new();
+    for ty in types {
+        if let Some(feature) = section_feature(&ty.section) {
+            features.insert(ty.name.clone(), feature);
+        }
+    }
+    for union in unions {
+        if let Some(feature) = union.section.as_ref().and_then(|section| section_feature(section)) {
+            features.insert(union.name.clone(), feature);
+        }
+    }
+    for (name, node) in graph {
+        let variants = match node {
+            Node::Union(variants) if !unions.iter().any(|union| union.name == *name) => variants,
+            _ => continue,
+        };
+        let variant_features: Vec<_> = variants
+            .iter()
+            .map(|(_, variant_type)| features.get(variant_type).cloned())
+            .collect();
+        if let Some(Some(feature)) = variant_features.first() {
+            if variant_features.iter().all(|other| *other == Some(*feature)) {
+                features.insert(name.to_string(), *feature);
+            }
+        }
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_util::ty;
+
+    fn in_section(name: &str, section: &str) -> Type {
+        Type {
+            section: section.to_owned(),
+            ..ty(name, TypeKind::Type, Vec::new())
+        }
+    }
+
+    fn variants(types: &[&str]) -> Vec<(String, String)> {
+        types.iter().map(|name| (name.to_string(), name.to_string())).collect()
+    }
+
+    #[test]
+    fn unions_take_the_section_their_variants_share() {
+        let types = vec![in_section("Sticker", "stickers"), in_section("StickerSet", "stickers"), in_section("Message", "available-types")];
+        let stickers = variants(&["Sticker", "StickerSet"]);
+        let mixed = variants(&["Sticker", "Message"]);
+        let mut graph = HashMap::new();
+        graph.insert("StickerOrStickerSet", Node::Union(&stickers));
+        graph.insert("StickerOrMessage", Node::Union(&mixed));
+        let features = section_features(&types, &[], &graph);
+        assert_eq!(features.get("Sticker"), Some(&"stickers"));
+        assert_eq!(features.get("StickerOrStickerSet"), Some(&"stickers"));
+        assert_eq!(features.get("Message"), None);
+        assert_eq!(features.get("StickerOrMessage"), None);
+    }
+}
@@ -0,0 +1,56 @@
+//! Types, fields and unions for tests, with whatever the tests don't look at left empty.
+
+use doc::Doc;
+use types::*;
+
+/// A required field type of one value, named like its Telegram type.
+pub fn field_type(name: &str) -> FieldType {
+    FieldType {
+        array_count: 0,
+        is_optional: false,
+        doc: None,
+        section: None,
+        name: name.to_owned(),
+        telegram_type: name.to_owned(),
+        kind: FieldKind::Simple,
+        is_boxed: false,
+    }
+}
+
+/// An optional field type of one value.
+pub fn optional(name: &str) -> FieldType {
+    FieldType {
+        is_optional: true,
+        ..field_type(name)
+    }
+}
+
+/// An undocumented union of the types `variants`.
+pub fn union(name: &str, variants: &[&str]) -> FieldType {
+    FieldType {
+        telegram_type: variants.join(" or "),
+        kind: FieldKind::Enum(variants.iter().map(|variant| (variant.to_string(), variant.to_string())).collect()),
+        ..field_type(name)
+    }
+}
+
+pub fn field(name: &str, field_type: FieldType) -> Field {
+    Field {
+        name: name.to_owned(),
+        field_type,
+        doc: Doc::default(),
+        since: None,
+    }
+}
+
+/// A type or method of the "Available types" section.
+pub fn ty(name: &str, kind: TypeKind, fields: Vec<Field>) -> Type {
+    Type {
+        name: name.to_owned(),
+        docs: Doc::default(),
+        section: "available-types".to_owned(),
+        since: None,
+        fields,
+        kind,
+    }
+}
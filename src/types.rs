@@ -1,7 +1,10 @@
+use doc::{Doc, Inline};
+
 #[derive(Debug)]
 pub struct TelegramMethod {
     pub name: String,
-    pub docs: Vec<String>,
+    pub docs: Doc,
+    pub section: String,
     pub fields: Vec<TelegramField>,
     pub return_type: TelegramFieldType,
 }
@@ -9,14 +12,15 @@ pub struct TelegramMethod {
 #[derive(Debug)]
 pub struct TelegramType {
     pub name: String,
-    pub docs: Vec<String>,
+    pub docs: Doc,
+    pub section: String,
     pub fields: Vec<TelegramField>,
 }
 
 #[derive(Debug)]
 pub struct TelegramField {
     pub name: String,
-    pub doc: String,
+    pub doc: Doc,
     pub telegram_type: TelegramFieldType,
 }
 
@@ -26,45 +30,73 @@ pub struct TelegramFieldType {
     pub is_optional: bool,
 }
 
+/// A release from "Recent changes", with the items of its change list.
+#[derive(Debug)]
+pub struct TelegramRelease {
+    pub version: String,
+    pub changes: Vec<Vec<Inline>>,
+}
+
+/// An h3 or h4 heading of the documentation.
+#[derive(Debug)]
+pub struct TelegramHeading {
+    pub level: u8,
+    pub anchor: String,
+    pub text: String,
+}
+
 #[derive(Debug)]
 pub enum TelegramTypeOrMethod {
     Type(TelegramType),
     Method(TelegramMethod),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Field {
     pub name: String,
     pub field_type: FieldType,
-    pub doc: String,
+    pub doc: Doc,
+    /// Bot API version that added the field, when "Recent changes" tells.
+    pub since: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Type {
     pub name: String,
-    pub docs: Vec<String>,
+    pub docs: Doc,
+    /// Anchor of the section of the documentation the type is in, e.g. `stickers`.
+    pub section: String,
+    /// Bot API version that added the type or method, when "Recent changes" tells.
+    pub since: Option<String>,
     pub fields: Vec<Field>,
     pub kind: TypeKind,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub struct FieldType {
     pub array_count: usize,
     pub is_optional: bool,
-    pub doc: Option<String>,
+    pub doc: Option<Doc>,
+    /// The section of a documented union, like `Type::section`.
+    pub section: Option<String>,
     pub name: String,
+    /// The type as written in the documentation, e.g. `Array of InputFile or String`.
+    pub telegram_type: String,
     pub kind: FieldKind,
     pub is_boxed: bool,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TypeKind {
     Type,
     Method(FieldType),
     Enum,
+    Client,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FieldKind {
     Simple,
     Enum(Vec<(String, String)>),
@@ -76,4 +108,9 @@ pub struct Module {
     pub module_name: String,
     pub module_type: String,
     pub contents: String,
+    /// Cargo features the module is only compiled with, besides the one of its section.
+    pub features: Vec<String>,
+    /// Feature of the section the module is in, with `--sections`, and the directory it is
+    /// written to.
+    pub section: Option<String>,
 }
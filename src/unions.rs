@@ -0,0 +1,204 @@
+//! Unions generated once per name, whether the documentation lists them or fields and results
+//! write them as "A or B", with what every use needs from the one definition.
+
+use generator::serde_derives;
+use options::Options;
+use std::collections::HashSet;
+use types::*;
+
+/// The one definition of a union, however many fields use it.
+pub struct Union {
+    pub field_type: FieldType,
+    /// Types and methods with a field of the union.
+    pub owners: Vec<String>,
+    /// Whether a method returns the union.
+    pub is_result: bool,
+    /// Where the definition comes from, for conflicts.
+    origin: String,
+}
+
+impl Union {
+    /// Whether the union derives `Serialize` and `Deserialize` outside of the fake server: each
+    /// as soon as one of its owners does, and `Deserialize` when a method returns it.
+    pub fn serde_derives(&self, return_types: &HashSet<String>) -> (bool, bool) {
+        let (mut serialize, mut deserialize) = (false, self.is_result);
+        for owner in &self.owners {
+            let (owner_serialize, owner_deserialize) = serde_derives(owner, return_types);
+            serialize |= owner_serialize;
+            deserialize |= owner_deserialize;
+        }
+        if !serialize && !deserialize {
+            deserialize = return_types.contains(&self.field_type.name);
+            serialize = !deserialize;
+        }
+        (serialize, deserialize)
+    }
+
+    /// Whether the union gets an `Unknown` variant for the ones Telegram adds later, which it only
+    /// does when it is read and `--non-exhaustive` is given.
+    pub fn has_fallback(&self, return_types: &HashSet<String>, options: &Options) -> bool {
+        options.non_exhaustive && self.serde_derives(return_types).1
+    }
+}
+
+/// One union per name, from the documented unions and the ones written as "A or B" in fields
+/// and results. Two definitions of a name only merge when they have the same variants and at most
+/// one of them is documented.
+pub fn intern_unions(types: &[Type], documented: &[FieldType]) -> Vec<Union> {
+    let mut unions = Vec::new();
+    for union in documented {
+        let origin = format!("the documentation of {}", union.name);
+        intern(&mut unions, union.clone(), origin);
+    }
+    for (owner, field, field_type) in references(types) {
+        if let FieldKind::Enum(_) = field_type.kind {
+            let definition = FieldType {
+                array_count: 0,
+                is_optional: false,
+                is_boxed: false,
+                ..field_type.clone()
+            };
+            let origin = match field {
+                Some(field) => format!("{}.{}", owner, field),
+                None => format!("the result of {}", owner),
+            };
+            intern(&mut unions, definition, origin);
+        }
+    }
+    for union in &mut unions {
+        for (owner, field, field_type) in references(types) {
+            if field_type.name != union.field_type.name {
+                continue;
+            }
+            match field {
+                Some(_) if !union.owners.iter().any(|other| other == owner) => union.owners.push(owner.to_owned()),
+                Some(_) => {}
+                None => union.is_result = true,
+            }
+        }
+    }
+    unions
+}
+
+fn intern(unions: &mut Vec<Union>, definition: FieldType, origin: String) {
+    let union = match unions.iter_mut().find(|union| union.field_type.name == definition.name) {
+        Some(union) => union,
+        None => {
+            unions.push(Union {
+                field_type: definition,
+                owners: Vec::new(),
+                is_result: false,
+                origin,
+            });
+            return;
+        }
+    };
+    if variants(&union.field_type.kind) != variants(&definition.kind) {
+        panic!(
+            "union {} has the variants {:?} in {} but {:?} in {}",
+            definition.name, union.field_type.kind, union.origin, definition.kind, origin
+        );
+    }
+    match (&union.field_type.doc, definition.doc) {
+        (Some(_), Some(_)) => panic!("union {} is documented in {} and {}", definition.name, union.origin, origin),
+        (None, Some(doc)) => {
+            union.field_type.doc = Some(doc);
+            union.field_type.section = definition.section;
+            union.origin = origin;
+        }
+        _ => {}
+    }
+}
+
+/// The variants of a union, in whatever order they are written.
+fn variants(kind: &FieldKind) -> HashSet<&(String, String)> {
+    match kind {
+        FieldKind::Enum(variants) => variants.iter().collect(),
+        FieldKind::Simple => HashSet::new(),
+    }
+}
+
+/// Every field type with the type or method it is in and the name of its field, or `None` for
+/// results.
+fn references(types: &[Type]) -> Vec<(&str, Option<&str>, &FieldType)> {
+    let mut references = Vec::new();
+    for ty in types {
+        if let TypeKind::Method(result) = &ty.kind {
+            references.push((ty.name.as_str(), None, result));
+        }
+        for field in &ty.fields {
+            references.push((ty.name.as_str(), Some(field.name.as_str()), &field.field_type));
+        }
+    }
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use doc::Doc;
+    use test_util::*;
+
+    fn documented(name: &str, variants: &[&str]) -> FieldType {
+        FieldType {
+            doc: Some(Doc::default()),
+            section: Some("available-types".to_owned()),
+            ..union(name, variants)
+        }
+    }
+
+    #[test]
+    fn interns_one_union_per_name() {
+        let mut optional = union("IntegerOrString", &["String", "Integer"]);
+        optional.is_optional = true;
+        let types = vec![
+            ty("SendMessage", TypeKind::Method(union("MessageOrTrue", &["Message", "True"])), vec![
+                field("chat_id", union("IntegerOrString", &["Integer", "String"])),
+                field("media", union("InputMedia", &["InputMediaPhoto"])),
+            ]),
+            ty("GetChat", TypeKind::Type, vec![field("chat_id", optional)]),
+        ];
+        let documented = vec![documented("InputMedia", &["InputMediaPhoto"])];
+        let unions = intern_unions(&types, &documented);
+        let names: Vec<_> = unions.iter().map(|union| union.field_type.name.as_str()).collect();
+        assert_eq!(names, vec!["InputMedia", "MessageOrTrue", "IntegerOrString"]);
+        assert_eq!(unions[0].field_type.section, Some("available-types".to_owned()));
+        assert!(unions[1].is_result && unions[1].owners.is_empty());
+        assert_eq!(unions[2].owners, vec!["SendMessage", "GetChat"]);
+        assert!(!unions[2].field_type.is_optional);
+    }
+
+    #[test]
+    fn only_read_unions_get_a_fallback() {
+        let types = vec![
+            ty("SendMessage", TypeKind::Method(union("MessageOrTrue", &["Message", "True"])), vec![
+                field("chat_id", union("IntegerOrString", &["Integer", "String"])),
+            ]),
+            ty("ChatMemberUpdated", TypeKind::Type, vec![field("new_chat_member", union("ChatMember", &["ChatMemberOwner"]))]),
+        ];
+        let return_types: HashSet<_> = vec!["ChatMemberUpdated".to_owned()].into_iter().collect();
+        let options = Options { non_exhaustive: true, ..Options::default() };
+        let unions = intern_unions(&types, &[]);
+        let fallbacks: Vec<_> = unions
+            .iter()
+            .filter(|union| union.has_fallback(&return_types, &options))
+            .map(|union| union.field_type.name.as_str())
+            .collect();
+        assert_eq!(fallbacks, vec!["MessageOrTrue", "ChatMember"]);
+        assert!(unions.iter().all(|union| !union.has_fallback(&return_types, &Options::default())));
+    }
+
+    #[test]
+    #[should_panic(expected = "union InputMedia has the variants")]
+    fn fails_on_different_variants() {
+        let types = vec![ty("SendMediaGroup", TypeKind::Type, vec![field("media", union("InputMedia", &["InputMediaVideo"]))])];
+        intern_unions(&types, &[documented("InputMedia", &["InputMediaPhoto"])]);
+    }
+
+    #[test]
+    #[should_panic(expected = "union InputMedia is documented in")]
+    fn fails_on_two_documentations() {
+        let documented = documented("InputMedia", &["InputMediaPhoto"]);
+        intern_unions(&[], &[documented.clone(), documented]);
+    }
+}
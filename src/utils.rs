@@ -15,7 +15,25 @@ pub fn snake_case(string: &str) -> String {
     new_string
 }
 
+/// Uppercases the first letter of `string`, if it has one.
 pub fn capitalize(string: &mut String) {
-    let uppercase = string[0..1].to_uppercase();
-    string.replace_range(0..1, &uppercase)
+    if let Some(first) = string.chars().next() {
+        let uppercase = first.to_uppercase().to_string();
+        string.replace_range(0..first.len_utf8(), &uppercase)
+    }
+}
+
+/// Lowercases the first letter of a method's type name to get its name in the API, e.g.
+/// `SendMessage` to `sendMessage`.
+pub fn camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Link to the documentation of a type or method, whose anchors are their lowercased names.
+pub fn doc_url(name: &str) -> String {
+    format!("https://core.telegram.org/bots/api#{}", name.to_lowercase())
 }
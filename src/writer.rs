@@ -1,5 +1,6 @@
 use codegen::Scope;
-use generator::generate_single_mod;
+use generator::{cfg_feature, generate_single_mod};
+use std::collections::BTreeMap;
 use std::fs;
 use std::iter::Peekable;
 use std::path::{Path, PathBuf};
@@ -11,6 +12,7 @@ pub fn write_mod_files<'a>(dir: &str, mut modules: Peekable<impl Iterator<Item =
         TypeKind::Type => write_types_mod(dir, modules),
         TypeKind::Method(_) => write_methods_mod(dir, modules),
         TypeKind::Enum => write_enums_mod(dir, modules),
+        TypeKind::Client => write_client_mod(dir, modules),
     }
 }
 
@@ -52,16 +54,58 @@ fn write_enums_mod<'a, P: AsRef<Path>>(dir: P, modules: impl Iterator<Item = &'a
     fs::write(path, string).unwrap();
 }
 
+fn write_client_mod<'a, P: AsRef<Path>>(dir: P, modules: impl Iterator<Item = &'a Module>) {
+    let mut path = dir.as_ref().to_path_buf();
+    let mut string = String::new();
+    path.push("client");
+    fs::create_dir_all(path.as_path()).unwrap();
+    write_module_file(&path, modules, &mut string);
+    path.push("mod");
+    path.set_extension("rs");
+    fs::write(path, string).unwrap();
+}
+
+/// Writes the dependencies and features the generated code needs to `Cargo.snippet.toml`,
+/// followed by `features`, each with the features it enables.
+pub fn write_cargo_snippet(dir: &str, features: &BTreeMap<&str, Vec<&str>>) {
+    let mut snippet = include_str!("../templates/cargo.toml").to_owned();
+    for (feature, enabled) in features {
+        let enabled: Vec<_> = enabled.iter().map(|feature| format!("{:?}", feature)).collect();
+        snippet.push_str(&format!("{} = [{}]\n", feature, enabled.join(", ")));
+    }
+    let mut path = PathBuf::from(dir);
+    path.push("Cargo.snippet.toml");
+    fs::write(path, snippet).unwrap();
+}
+
+/// Writes every module to `path`, or to the directory of its section, which `string` then
+/// declares behind the feature of the section.
 pub fn write_module_file<'a>(
     path: &PathBuf,
     modules: impl Iterator<Item = &'a Module>,
     string: &mut String,
 ) {
+    let mut sections = BTreeMap::new();
     for module in modules {
         let mut path = path.clone();
+        let mut mod_string = &mut *string;
+        if let Some(section) = &module.section {
+            path.push(section);
+            fs::create_dir_all(path.as_path()).unwrap();
+            mod_string = sections.entry(section).or_insert_with(String::new);
+        }
         path.push(&module.module_name);
         path.set_extension("rs");
         fs::write(path, &module.contents).unwrap();
-        generate_single_mod(module, string);
+        generate_single_mod(module, mod_string);
+    }
+    for (section, section_string) in sections {
+        let mut path = path.clone();
+        path.push(section);
+        path.push("mod");
+        path.set_extension("rs");
+        fs::write(path, section_string).unwrap();
+        let cfg = format!("#[{}]\n", cfg_feature(section));
+        string.push_str(&format!("{}pub mod {};\n{}pub use self::{}::*;\n", cfg, section, cfg, section));
     }
 }
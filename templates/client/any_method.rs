@@ -0,0 +1,7 @@
+use crate::methods::*;
+use crate::types::*;
+
+/// A method that is a variant of [`AnyMethod`].
+pub trait AnyMethodVariant: Method + Into<AnyMethod> {
+    fn from_any(method: &AnyMethod) -> Option<&Self>;
+}
@@ -0,0 +1,152 @@
+use crate::client::*;
+use crate::types::*;
+use std::future::Future;
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// [`Transport`] over ureq, which performs every request on a thread of its own.
+///
+/// The futures it returns never block, so it works with any executor, including [`block_on`].
+#[derive(Debug, Clone)]
+pub struct BlockingTransport {
+    agent: ureq::Agent,
+}
+
+impl BlockingTransport {
+    pub fn new(agent: ureq::Agent) -> Self {
+        BlockingTransport { agent }
+    }
+}
+
+impl Default for BlockingTransport {
+    fn default() -> Self {
+        BlockingTransport::new(ureq::Agent::new())
+    }
+}
+
+impl Transport for BlockingTransport {
+    fn send(&self, url: String, request: HttpRequestParts) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+        let agent = self.agent.clone();
+        spawn_blocking(move || {
+            let (content_type, body) = http_body(&request).map_err(TransportError::new)?;
+            let method = match request.http_method {
+                HttpMethod::Get => "GET",
+                HttpMethod::Post => "POST",
+            };
+            let mut http_request = agent.request(method, &url);
+            if let Some(content_type) = content_type {
+                http_request = http_request.set("Content-Type", &content_type);
+            }
+            let result = match request.http_method {
+                HttpMethod::Get => http_request.call(),
+                HttpMethod::Post => http_request.send_bytes(&body),
+            };
+            let response = match result {
+                Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+                Err(error) => return Err(TransportError::new(error)),
+            };
+            let status = response.status();
+            let mut body = Vec::new();
+            response.into_reader().read_to_end(&mut body).map_err(TransportError::new)?;
+            Ok(HttpResponse { status, body })
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        spawn_blocking(move || thread::sleep(duration))
+    }
+}
+
+struct BlockingState<T> {
+    output: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// Output of a task running on a thread of its own, see [`spawn_blocking`].
+struct BlockingTask<T>(Arc<Mutex<BlockingState<T>>>);
+
+impl<T> Future for BlockingTask<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+        let mut state = self.0.lock().unwrap();
+        match state.output.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            Some(Err(panic)) => panic::resume_unwind(panic),
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Runs `task` on a new thread, so that its blocking IO never stalls the executor polling the
+/// returned future. A panic of `task` is raised again when the future is polled.
+fn spawn_blocking<T, F>(task: F) -> BoxFuture<'static, T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let state = Arc::new(Mutex::new(BlockingState {
+        output: None,
+        waker: None,
+    }));
+    let task_state = state.clone();
+    thread::spawn(move || {
+        let output = panic::catch_unwind(AssertUnwindSafe(task));
+        let mut state = task_state.lock().unwrap();
+        state.output = Some(output);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    });
+    Box::pin(BlockingTask(state))
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs `future` to completion on the current thread.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn runs_blocking_tasks_on_their_own_thread() {
+        let caller = thread::current().id();
+        assert_ne!(block_on(spawn_blocking(move || thread::current().id())), caller);
+        let start = Instant::now();
+        block_on(BlockingTransport::default().sleep(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    #[should_panic(expected = "task failed")]
+    fn raises_panics_of_tasks_again() {
+        block_on(spawn_blocking(|| panic!("task failed")));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::client::*;
+use crate::methods::*;
+use crate::types::*;
+use std::future::Future;
+use std::time::Duration;
+
+pub const DEFAULT_BASE_URL: &str = "https://api.telegram.org";
+
+/// Bot API client, with one method per Bot API method on top of [`Bot::execute`].
+#[derive(Debug, Clone)]
+pub struct Bot<T> {
+    token: String,
+    base_url: String,
+    encoding: Encoding,
+    retry: RetryPolicy,
+    transport: T,
+}
+
+impl<T: Transport> Bot<T> {
+    pub fn new(token: impl Into<String>, transport: T) -> Self {
+        Bot {
+            token: token.into(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            encoding: Encoding::Json,
+            retry: RetryPolicy::default(),
+            transport,
+        }
+    }
+
+    /// Sends requests somewhere else than [`DEFAULT_BASE_URL`], e.g. to a local server in tests.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Encoding of method parameters; calls uploading files always use multipart.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Retries calls failing with `retry_after` or `migrate_to_chat_id`, see [`RetryPolicy`].
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Full URL of `request`, `<base_url>/bot<token>/<method>?<query>`.
+    pub fn url(&self, request: &HttpRequestParts) -> String {
+        let mut url = format!("{}/bot{}/{}", self.base_url.trim_end_matches('/'), self.token, request.method);
+        if !request.query.is_empty() {
+            url.push('?');
+            url.push_str(&request.query);
+        }
+        url
+    }
+
+    /// Calls any method and returns its result, retrying according to the [`RetryPolicy`].
+    ///
+    /// A retry uploads the same files again; the contents of an [`InputFile::Reader`] are kept
+    /// in memory after the first attempt read them.
+    pub async fn execute<M: Method>(&self, mut method: M) -> Result<M::Response, Error> {
+        let mut attempt = 0;
+        loop {
+            let error = match self.execute_once(&method).await {
+                Err(Error::Api(error)) if attempt < self.retry.max_retries() => error,
+                result => return result,
+            };
+            attempt += 1;
+            if let Some(retry_after) = error.retry_after {
+                self.retry.notify(&RetryEvent::FloodControl {
+                    method: M::name(),
+                    retry_after,
+                    attempt,
+                });
+                self.transport.sleep(Duration::from_secs(retry_after.max(0) as u64)).await;
+            } else if let Some(migrate_to_chat_id) = error.migrate_to_chat_id.filter(|&chat_id| method.migrate_chat(chat_id)) {
+                self.retry.notify(&RetryEvent::ChatMigrated {
+                    method: M::name(),
+                    migrate_to_chat_id,
+                    attempt,
+                });
+            } else {
+                return Err(Error::Api(error));
+            }
+        }
+    }
+
+    async fn execute_once<M: Method>(&self, method: &M) -> Result<M::Response, Error> {
+        let request = method.to_request(self.encoding);
+        let url = self.url(&request);
+        let response = self.transport.send(url, request).await?;
+        let result = M::parse_response(&response.body).map_err(|error| Error::Parse {
+            status: response.status,
+            error,
+        })?;
+        Ok(result?)
+    }
+}
@@ -0,0 +1,36 @@
+use crate::client::*;
+use crate::types::*;
+use std::fmt;
+
+/// Everything that can go wrong when calling a method through a [`Bot`].
+#[derive(Debug)]
+pub enum Error {
+    Transport(TransportError),
+    /// The response body was not a valid Bot API response.
+    Parse { status: u16, error: serde_json::Error },
+    Api(ApiError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Transport(error) => write!(f, "transport error: {}", error),
+            Error::Parse { status, error } => write!(f, "invalid response with status {}: {}", status, error),
+            Error::Api(error) => write!(f, "api error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<TransportError> for Error {
+    fn from(error: TransportError) -> Self {
+        Error::Transport(error)
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        Error::Api(error)
+    }
+}
@@ -0,0 +1,492 @@
+use crate::client::*;
+use crate::methods::*;
+use crate::types::*;
+use serde::de::{self, IntoDeserializer};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A placeholder value of a type the Bot API returns, answered when no response was scripted.
+pub trait Fake {
+    fn fake() -> Self;
+}
+
+impl Fake for Integer {
+    fn fake() -> Self {
+        0
+    }
+}
+
+impl Fake for Float {
+    fn fake() -> Self {
+        0.0
+    }
+}
+
+impl Fake for bool {
+    fn fake() -> Self {
+        true
+    }
+}
+
+impl Fake for True {
+    fn fake() -> Self {
+        True
+    }
+}
+
+impl Fake for String {
+    fn fake() -> Self {
+        String::new()
+    }
+}
+
+impl Fake for UnixTime {
+    fn fake() -> Self {
+        UnixTime(0)
+    }
+}
+
+impl Fake for InputFile {
+    fn fake() -> Self {
+        InputFile::FileId(String::new())
+    }
+}
+
+impl<T> Fake for Option<T> {
+    fn fake() -> Self {
+        None
+    }
+}
+
+impl<T> Fake for Vec<T> {
+    fn fake() -> Self {
+        Vec::new()
+    }
+}
+
+impl<T: Fake> Fake for Box<T> {
+    fn fake() -> Self {
+        Box::new(T::fake())
+    }
+}
+
+/// Checks the constraints the documentation puts on parameters, beyond their types.
+pub trait Validate {
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Length of a string parameter, counted in characters or in bytes.
+fn check_length(name: &str, value: &str, min: usize, max: usize, bytes: bool) -> Result<(), String> {
+    let (length, unit) = if bytes {
+        (value.len(), "bytes")
+    } else {
+        (value.chars().count(), "characters")
+    };
+    if length < min || length > max {
+        return Err(format!("{} must be {}-{} {} long", name, min, max, unit));
+    }
+    Ok(())
+}
+
+/// A method call the fake server accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeCall {
+    /// Name of the method, as spelled in the documentation.
+    pub method: &'static str,
+    /// Parameters as received; values sent as form fields are strings.
+    pub params: serde_json::Map<String, serde_json::Value>,
+    /// Uploaded files by their `attach://` name.
+    pub files: HashMap<String, Vec<u8>>,
+}
+
+impl FakeCall {
+    /// Reads the parameters as the method `M`, or `None` if this is a call to another method.
+    pub fn parse<M: Method + de::DeserializeOwned>(&self) -> Option<M> {
+        if self.method != M::name() {
+            return None;
+        }
+        parse_params(self.params.clone()).ok()
+    }
+}
+
+#[derive(Default)]
+struct State {
+    calls: Vec<FakeCall>,
+    responses: HashMap<&'static str, VecDeque<Result<serde_json::Value, ApiError>>>,
+    updates: VecDeque<Update>,
+    last_update_id: Integer,
+}
+
+/// An in-memory Bot API for tests, serving `/bot<token>/<method>` without any network access.
+///
+/// Every request is read into its method struct and validated. Accepted calls are recorded and
+/// answered with the next scripted response for the method, or with a [`Fake`] value of its
+/// response type. `getUpdates` is answered from the queued updates instead. Use it directly as
+/// the [`Transport`] of a `Bot`, or serve it over HTTP with `FakeServerService`.
+pub struct FakeServer {
+    token: String,
+    state: Mutex<State>,
+}
+
+impl FakeServer {
+    pub fn new(token: impl Into<String>) -> Self {
+        FakeServer {
+            token: token.into(),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Answers the next call to `M` with `response`.
+    pub fn push_response<M: Method>(&self, response: M::Response)
+    where
+        M::Response: serde::Serialize,
+    {
+        let response = serde_json::to_value(response).expect("responses serialize to JSON");
+        self.push(M::name(), Ok(response));
+    }
+
+    /// Fails the next call to `M` with `error`.
+    pub fn push_error<M: Method>(&self, error: ApiError) {
+        self.push(M::name(), Err(error));
+    }
+
+    fn push(&self, method: &'static str, response: Result<serde_json::Value, ApiError>) {
+        let mut state = self.state.lock().unwrap();
+        state.responses.entry(method).or_default().push_back(response);
+    }
+
+    /// Queues `update` for `getUpdates`, giving it the next `update_id`.
+    pub fn push_update(&self, mut update: Update) {
+        let mut state = self.state.lock().unwrap();
+        state.last_update_id += 1;
+        update.update_id = state.last_update_id;
+        state.updates.push_back(update);
+    }
+
+    /// Calls accepted so far, oldest first.
+    pub fn calls(&self) -> Vec<FakeCall> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Accepted calls to `M`, oldest first.
+    pub fn calls_of<M: Method + de::DeserializeOwned>(&self) -> Vec<M> {
+        self.state.lock().unwrap().calls.iter().filter_map(FakeCall::parse).collect()
+    }
+
+    /// Handles a request for `target`, a path with an optional query string, returning the
+    /// status and JSON body to answer with.
+    pub fn handle(&self, target: &str, content_type: Option<&str>, body: &[u8]) -> HttpResponse {
+        let (path, query) = match target.find('?') {
+            Some(index) => (&target[..index], &target[index + 1..]),
+            None => (target, ""),
+        };
+        let mut segments = path.rsplit('/');
+        let method = segments.next().unwrap_or_default();
+        if segments.next() != Some(&format!("bot{}", self.token)) {
+            return error_response(401, "Unauthorized");
+        }
+        let mut params = serde_json::Map::new();
+        let mut files = HashMap::new();
+        for (name, value) in form_urldecode(query) {
+            params.insert(name, serde_json::Value::String(value));
+        }
+        let content_type = content_type.unwrap_or_default();
+        if content_type.starts_with("application/json") {
+            match serde_json::from_slice(body) {
+                Ok(serde_json::Value::Object(object)) => params.extend(object),
+                _ => return error_response(400, "Bad Request: invalid JSON body"),
+            }
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            for (name, value) in form_urldecode(&String::from_utf8_lossy(body)) {
+                params.insert(name, serde_json::Value::String(value));
+            }
+        } else if content_type.starts_with("multipart/form-data") {
+            let boundary = match content_type.split("boundary=").nth(1) {
+                Some(boundary) => boundary.trim_matches('"'),
+                None => return error_response(400, "Bad Request: missing multipart boundary"),
+            };
+            for (name, is_file, value) in multipart_parts(body, boundary) {
+                if is_file {
+                    files.insert(name, value);
+                } else {
+                    params.insert(name, serde_json::Value::String(String::from_utf8_lossy(&value).into_owned()));
+                }
+            }
+        }
+        match dispatch(self, method, params, files) {
+            Some(Ok(result)) => json_response(200, serde_json::json!({ "ok": true, "result": result })),
+            Some(Err(error)) => {
+                let mut body = serde_json::json!({
+                    "ok": false,
+                    "error_code": error.error_code,
+                    "description": error.description,
+                });
+                let mut parameters = serde_json::Map::new();
+                if let Some(retry_after) = error.retry_after {
+                    parameters.insert("retry_after".to_owned(), retry_after.into());
+                }
+                if let Some(migrate_to_chat_id) = error.migrate_to_chat_id {
+                    parameters.insert("migrate_to_chat_id".to_owned(), migrate_to_chat_id.into());
+                }
+                if !parameters.is_empty() {
+                    body["parameters"] = parameters.into();
+                }
+                json_response(error.error_code as u16, body)
+            }
+            None => error_response(404, "Not Found: method not found"),
+        }
+    }
+
+    /// Reads, validates and records a call to `M`, then answers it.
+    fn call<M>(
+        &self,
+        params: serde_json::Map<String, serde_json::Value>,
+        files: HashMap<String, Vec<u8>>,
+    ) -> Result<serde_json::Value, ApiError>
+    where
+        M: Method + Validate + de::DeserializeOwned,
+        M::Response: Fake + serde::Serialize,
+    {
+        let method: M = parse_params(params.clone()).map_err(|error| bad_request(&error.to_string()))?;
+        method.validate().map_err(|error| bad_request(&error))?;
+        let mut state = self.state.lock().unwrap();
+        state.calls.push(FakeCall {
+            method: M::name(),
+            params,
+            files,
+        });
+        if let Some(response) = state.responses.get_mut(M::name()).and_then(VecDeque::pop_front) {
+            return response;
+        }
+        Ok(serde_json::to_value(M::Response::fake()).expect("responses serialize to JSON"))
+    }
+
+    /// Answers `getUpdates` from the queue, forgetting the updates confirmed by `offset`.
+    ///
+    /// Never waits for updates, whatever the `timeout`.
+    fn get_updates(
+        &self,
+        params: serde_json::Map<String, serde_json::Value>,
+        files: HashMap<String, Vec<u8>>,
+    ) -> Result<serde_json::Value, ApiError> {
+        let method: GetUpdates = parse_params(params.clone()).map_err(|error| bad_request(&error.to_string()))?;
+        method.validate().map_err(|error| bad_request(&error))?;
+        let mut state = self.state.lock().unwrap();
+        state.calls.push(FakeCall {
+            method: GetUpdates::name(),
+            params,
+            files,
+        });
+        if let Some(offset) = method.offset {
+            while state.updates.front().map_or(false, |update| update.update_id < offset) {
+                state.updates.pop_front();
+            }
+        }
+        let limit = method.limit.unwrap_or(100).max(1) as usize;
+        let updates: Vec<_> = state.updates.iter().take(limit).collect();
+        Ok(serde_json::to_value(updates).expect("responses serialize to JSON"))
+    }
+}
+
+impl Transport for FakeServer {
+    fn send(&self, url: String, request: HttpRequestParts) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+        Box::pin(async move {
+            let (content_type, body) = http_body(&request).map_err(TransportError::new)?;
+            let target = match url.find("://") {
+                Some(index) => url[index + 3..].find('/').map_or("/", |start| &url[index + 3 + start..]),
+                None => &url,
+            };
+            Ok(self.handle(target, content_type.as_deref(), &body))
+        })
+    }
+
+    fn sleep(&self, _duration: Duration) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+fn bad_request(description: &str) -> ApiError {
+    ApiError {
+        error_code: 400,
+        description: format!("Bad Request: {}", description),
+        retry_after: None,
+        migrate_to_chat_id: None,
+    }
+}
+
+fn error_response(status: u16, description: &str) -> HttpResponse {
+    json_response(
+        status,
+        serde_json::json!({ "ok": false, "error_code": status, "description": description }),
+    )
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: body.to_string().into_bytes(),
+    }
+}
+
+fn parse_params<M: de::DeserializeOwned>(params: serde_json::Map<String, serde_json::Value>) -> serde_json::Result<M> {
+    let params = params.into_iter().map(|(name, value)| (name, Param(value)));
+    M::deserialize(de::value::MapDeserializer::new(params))
+}
+
+/// A parameter value that may have been sent as a form field, where every value is a string
+/// and objects and arrays are JSON-encoded.
+struct Param(serde_json::Value);
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for Param {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Param {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            serde_json::Value::String(string) => match serde_json::from_str::<serde_json::Value>(&string) {
+                Ok(serde_json::Value::String(_)) | Err(_) => visitor.visit_string(string),
+                Ok(value) => de::Deserializer::deserialize_any(value, visitor),
+            },
+            value => de::Deserializer::deserialize_any(value, visitor),
+        }
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        de::Deserializer::deserialize_string(self.0, visitor)
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        de::Deserializer::deserialize_string(self.0, visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        match self.0 {
+            serde_json::Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(Param(value)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> serde_json::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char bytes byte_buf unit unit_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+fn form_urldecode(encoded: &str) -> Vec<(String, String)> {
+    encoded
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut pair = pair.splitn(2, '=');
+            let name = percent_decode(pair.next().unwrap_or_default());
+            let value = percent_decode(pair.next().unwrap_or_default());
+            (name, value)
+        })
+        .collect()
+}
+
+fn percent_decode(string: &str) -> String {
+    let bytes = string.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match (bytes[index], bytes.get(index + 1).and_then(hex), bytes.get(index + 2).and_then(hex)) {
+            (b'%', Some(high), Some(low)) => {
+                decoded.push(high << 4 | low);
+                index += 2;
+            }
+            (b'+', _, _) => decoded.push(b' '),
+            (byte, _, _) => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn hex(digit: &u8) -> Option<u8> {
+    (*digit as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Splits a `multipart/form-data` body into its parts, as name, whether it is a file, and
+/// contents.
+fn multipart_parts(body: &[u8], boundary: &str) -> Vec<(String, bool, Vec<u8>)> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut parts = Vec::new();
+    let mut rest = match find(body, &delimiter) {
+        Some(start) => &body[start + delimiter.len()..],
+        None => return parts,
+    };
+    while let Some(end) = find(rest, &delimiter) {
+        let part = &rest[..end];
+        rest = &rest[end + delimiter.len()..];
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        let (headers, contents) = match find(part, b"\r\n\r\n") {
+            Some(end) => (String::from_utf8_lossy(&part[..end]), &part[end + 4..]),
+            None => continue,
+        };
+        let disposition = headers
+            .lines()
+            .find(|line| line.to_ascii_lowercase().starts_with("content-disposition:"))
+            .unwrap_or_default();
+        let name = match disposition.split("name=\"").nth(1).and_then(|name| name.split('"').next()) {
+            Some(name) => name.to_owned(),
+            None => continue,
+        };
+        parts.push((name, disposition.contains("filename="), contents.to_vec()));
+    }
+    parts
+}
+
+fn find(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    bytes.windows(needle.len()).position(|window| window == needle)
+}
+
+/// hyper service around a [`FakeServer`], to test bots that do their own HTTP.
+#[cfg(feature = "hyper")]
+#[derive(Clone)]
+pub struct FakeServerService(pub std::sync::Arc<FakeServer>);
+
+#[cfg(feature = "hyper")]
+impl hyper::service::Service<hyper::Request<hyper::Body>> for FakeServerService {
+    type Response = hyper::Response<hyper::Body>;
+    type Error = std::convert::Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _context: &mut std::task::Context) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: hyper::Request<hyper::Body>) -> Self::Future {
+        let server = self.0.clone();
+        Box::pin(async move {
+            let target = request.uri().path_and_query().map_or("/", |target| target.as_str()).to_owned();
+            let content_type = request
+                .headers()
+                .get(hyper::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned);
+            let response = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => server.handle(&target, content_type.as_deref(), &body),
+                Err(_) => error_response(400, "Bad Request: unreadable body"),
+            };
+            Ok(hyper::Response::builder()
+                .status(response.status)
+                .header(hyper::header::CONTENT_TYPE, "application/json")
+                .body(hyper::Body::from(response.body))
+                .expect("fake server response is valid"))
+        })
+    }
+}
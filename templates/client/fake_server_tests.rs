@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send<M: Method>(server: &FakeServer, method: &M, encoding: Encoding) -> HttpResponse {
+        let request = encode(method, encoding);
+        let (content_type, body) = http_body(&request).unwrap();
+        let target = format!("/bottoken/{}?{}", request.method, request.query);
+        server.handle(&target, content_type.as_deref(), &body)
+    }
+
+    #[test]
+    fn reads_calls_back_in_every_encoding() {
+        let method = SendMessage::builder().chat_id(1).text("42 & \"a+b\" é").reply_to_message_id(3).build();
+        for encoding in [Encoding::Query, Encoding::UrlEncoded, Encoding::Json] {
+            let server = FakeServer::new("token");
+            assert_eq!(send(&server, &method, encoding).status, 200, "{:?}", encoding);
+            assert_eq!(server.calls_of::<SendMessage>(), vec![method.clone()], "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn reads_uploads_back_by_their_attach_name() {
+        let server = FakeServer::new("token");
+        let method = SendDocument::builder()
+            .chat_id("@channel".to_owned())
+            .document(InputFile::memory("a.txt", "contents"))
+            .caption("c")
+            .build();
+        assert_eq!(send(&server, &method, Encoding::Json).status, 200);
+        let call = &server.calls()[0];
+        assert_eq!(call.files.get("file0").map(Vec::as_slice), Some(&b"contents"[..]));
+        let received = call.parse::<SendDocument>().unwrap();
+        assert_eq!(received.document, InputFile::memory("file0", Vec::new()));
+        assert_eq!(received.chat_id, ChatIdOrUsername::from("@channel".to_owned()));
+    }
+
+    #[test]
+    fn rejects_other_tokens() {
+        let server = FakeServer::new("token");
+        assert_eq!(server.handle("/botother/getMe", None, b"").status, 401);
+        assert!(server.calls().is_empty());
+    }
+
+    #[test]
+    fn splits_multipart_bodies() {
+        let body = b"--xyz\r\nContent-Disposition: form-data; name=\"chat_id\"\r\n\r\n1\r\n\
+                     --xyz\r\nContent-Disposition: form-data; name=\"file0\"; filename=\"a.txt\"\r\n\r\na\r\nb\r\n\
+                     --xyz--\r\n";
+        assert_eq!(multipart_parts(body, "xyz"), vec![
+            ("chat_id".to_owned(), false, b"1".to_vec()),
+            ("file0".to_owned(), true, b"a\r\nb".to_vec()),
+        ]);
+    }
+
+    #[test]
+    fn decodes_percent_encoding() {
+        assert_eq!(percent_decode("a+b%26c%C3%A9%2"), "a b&cé%2");
+        assert_eq!(form_urldecode("a=1&&b=%3D"), vec![("a".to_owned(), "1".to_owned()), ("b".to_owned(), "=".to_owned())]);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::client::*;
+use crate::types::*;
+use std::time::Duration;
+
+/// [`Transport`] over a hyper client; the connector decides about TLS and the runtime.
+#[derive(Debug, Clone)]
+pub struct HyperTransport<C> {
+    client: hyper::Client<C, hyper::Body>,
+}
+
+impl<C> HyperTransport<C> {
+    pub fn new(client: hyper::Client<C, hyper::Body>) -> Self {
+        HyperTransport { client }
+    }
+}
+
+impl<C> Transport for HyperTransport<C>
+where
+    C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+    fn send(&self, url: String, request: HttpRequestParts) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+        Box::pin(async move {
+            let (content_type, body) = http_body(&request).map_err(TransportError::new)?;
+            let method = match request.http_method {
+                HttpMethod::Get => hyper::Method::GET,
+                HttpMethod::Post => hyper::Method::POST,
+            };
+            let mut builder = hyper::Request::builder().method(method).uri(url);
+            if let Some(content_type) = content_type {
+                builder = builder.header(hyper::header::CONTENT_TYPE, content_type);
+            }
+            let http_request = builder.body(hyper::Body::from(body)).map_err(TransportError::new)?;
+            let response = self.client.request(http_request).await.map_err(TransportError::new)?;
+            let status = response.status().as_u16();
+            let body = hyper::body::to_bytes(response.into_body()).await.map_err(TransportError::new)?;
+            Ok(HttpResponse {
+                status,
+                body: body.to_vec(),
+            })
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
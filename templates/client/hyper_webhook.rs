@@ -0,0 +1,57 @@
+use crate::client::*;
+use crate::types::*;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// hyper service around a [`Webhook`].
+pub struct WebhookService<F>(Arc<Webhook<F>>);
+
+impl<F> WebhookService<F> {
+    pub fn new(webhook: Webhook<F>) -> Self {
+        WebhookService(Arc::new(webhook))
+    }
+}
+
+impl<F> Clone for WebhookService<F> {
+    fn clone(&self) -> Self {
+        WebhookService(self.0.clone())
+    }
+}
+
+impl<F, Fut> hyper::service::Service<hyper::Request<hyper::Body>> for WebhookService<F>
+where
+    F: Fn(Update) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Option<WebhookReply>> + Send + 'static,
+{
+    type Response = hyper::Response<hyper::Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _context: &mut Context) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: hyper::Request<hyper::Body>) -> Self::Future {
+        let webhook = self.0.clone();
+        Box::pin(async move {
+            let path = request.uri().path().to_owned();
+            let response = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => webhook.handle(&path, &body).await,
+                Err(_) => WebhookResponse {
+                    status: 400,
+                    content_type: None,
+                    body: Vec::new(),
+                },
+            };
+            let mut builder = hyper::Response::builder().status(response.status);
+            if let Some(content_type) = response.content_type {
+                builder = builder.header(hyper::header::CONTENT_TYPE, content_type);
+            }
+            Ok(builder
+                .body(hyper::Body::from(response.body))
+                .expect("webhook response is valid"))
+        })
+    }
+}
@@ -0,0 +1,132 @@
+use crate::client::*;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+
+/// A `Result<M::Response, Error>` for the method `M` it was queued for.
+type Response = Box<dyn Any + Send>;
+type Handler = Box<dyn FnMut(&AnyMethod) -> Response + Send>;
+
+#[derive(Default)]
+struct MockState {
+    calls: Vec<AnyMethod>,
+    responses: HashMap<&'static str, VecDeque<Response>>,
+    handlers: HashMap<&'static str, Handler>,
+}
+
+/// A [`BotApi`] for unit tests, without any HTTP.
+///
+/// Every call is recorded, then answered with the next queued response for its method, or
+/// else by the handler set for it. A call with neither panics.
+#[derive(Default)]
+pub struct MockBot {
+    state: Mutex<MockState>,
+}
+
+impl MockBot {
+    pub fn new() -> Self {
+        MockBot::default()
+    }
+
+    /// Answers the next call to `M` with `response`.
+    pub fn push_response<M>(&self, response: Result<M::Response, Error>)
+    where
+        M: AnyMethodVariant,
+        M::Response: Send + 'static,
+    {
+        let mut state = self.state.lock().unwrap();
+        state.responses.entry(M::name()).or_default().push_back(Box::new(response));
+    }
+
+    /// Answers the calls to `M` that have no queued response with `handler`.
+    pub fn respond_with<M, F>(&self, mut handler: F)
+    where
+        M: AnyMethodVariant,
+        M::Response: Send + 'static,
+        F: FnMut(&M) -> Result<M::Response, Error> + Send + 'static,
+    {
+        let handler: Handler = Box::new(move |method| {
+            let method = M::from_any(method).expect("handlers only get calls to their method");
+            Box::new(handler(method))
+        });
+        self.state.lock().unwrap().handlers.insert(M::name(), handler);
+    }
+
+    /// Calls made so far, oldest first.
+    pub fn calls(&self) -> Vec<AnyMethod> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Calls to `M` made so far, oldest first.
+    pub fn calls_of<M: AnyMethodVariant + Clone>(&self) -> Vec<M> {
+        let state = self.state.lock().unwrap();
+        state.calls.iter().filter_map(M::from_any).cloned().collect()
+    }
+
+    /// Records `method` and answers it; every [`BotApi`] function of the mock calls this.
+    pub fn call<M>(&self, method: M) -> BoxFuture<'_, Result<M::Response, Error>>
+    where
+        M: AnyMethodVariant,
+        M::Response: Send + 'static,
+    {
+        let method = method.into();
+        let queued = self.state.lock().unwrap().responses.get_mut(M::name()).and_then(VecDeque::pop_front);
+        let response = match queued {
+            Some(response) => response,
+            None => {
+                // The handler runs unlocked, so it may inspect the mock itself.
+                let handler = self.state.lock().unwrap().handlers.remove(M::name());
+                let mut handler = handler.unwrap_or_else(|| panic!("MockBot has no response for {}", M::name()));
+                let response = handler(&method);
+                self.state.lock().unwrap().handlers.entry(M::name()).or_insert(handler);
+                response
+            }
+        };
+        self.state.lock().unwrap().calls.push(method);
+        let response = *response
+            .downcast::<Result<M::Response, Error>>()
+            .expect("responses are queued for their method");
+        Box::pin(async move { response })
+    }
+}
+
+impl fmt::Debug for MockBot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MockBot").field("calls", &self.state.lock().unwrap().calls).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::methods::*;
+    use crate::types::*;
+
+    fn typing(chat_id: Integer) -> SendChatAction {
+        SendChatAction::builder().chat_id(chat_id).action("typing").build()
+    }
+
+    #[test]
+    fn answers_with_queued_responses_then_the_handler() {
+        let mock = MockBot::new();
+        mock.push_response::<SendChatAction>(Err(Error::Api(ApiError {
+            error_code: 400,
+            description: "Bad Request: chat not found".to_owned(),
+            retry_after: None,
+            migrate_to_chat_id: None,
+        })));
+        mock.respond_with::<SendChatAction, _>(|_| Ok(True));
+        assert!(ready(mock.send_chat_action(typing(1))).is_err());
+        assert_eq!(ready(mock.send_chat_action(typing(2))).unwrap(), True);
+        assert_eq!(ready(mock.send_chat_action(typing(3))).unwrap(), True);
+        assert_eq!(mock.calls_of::<SendChatAction>(), vec![typing(1), typing(2), typing(3)]);
+        assert_eq!(mock.calls()[0].name(), "sendChatAction");
+    }
+
+    #[test]
+    #[should_panic(expected = "MockBot has no response for sendChatAction")]
+    fn panics_without_a_response() {
+        let _ = ready(MockBot::new().send_chat_action(typing(1)));
+    }
+}
@@ -0,0 +1,27 @@
+/// A method as described in the documentation, for handling methods generically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MethodInfo {
+    /// Name of the method, e.g. `sendMessage`.
+    pub name: &'static str,
+    pub params: &'static [ParamInfo],
+    /// Type of the result, as written in the documentation.
+    pub return_type: &'static str,
+    pub doc_url: &'static str,
+}
+
+impl MethodInfo {
+    /// Looks a method up by name, ignoring case as the Bot API does.
+    pub fn find(name: &str) -> Option<&'static MethodInfo> {
+        METHODS.iter().find(|method| method.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A parameter of a method as described in the documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParamInfo {
+    pub name: &'static str,
+    /// Type of the parameter, as written in the documentation, e.g. `Integer or String`.
+    pub telegram_type: &'static str,
+    pub required: bool,
+    pub doc: &'static str,
+}
@@ -0,0 +1,63 @@
+use crate::types::*;
+use std::fmt;
+use std::sync::Arc;
+
+/// What the [`Bot`](crate::client::Bot) did about a failed call before retrying it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RetryEvent {
+    /// Flood control was hit; the call is repeated after `retry_after` seconds.
+    FloodControl {
+        method: &'static str,
+        retry_after: Integer,
+        attempt: u32,
+    },
+    /// The group became a supergroup; the call is repeated with `chat_id` replaced.
+    ChatMigrated {
+        method: &'static str,
+        migrate_to_chat_id: Integer,
+        attempt: u32,
+    },
+}
+
+/// How a [`Bot`](crate::client::Bot) reacts to `retry_after` and `migrate_to_chat_id` in
+/// `ResponseParameters`. The default never retries.
+#[derive(Clone, Default)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    on_event: Option<Arc<dyn Fn(&RetryEvent) + Send + Sync>>,
+}
+
+impl RetryPolicy {
+    /// Retries every call at most `max_retries` times.
+    pub fn new(max_retries: u32) -> Self {
+        RetryPolicy {
+            max_retries,
+            on_event: None,
+        }
+    }
+
+    /// Calls `on_event` before every retry, e.g. to report chat migrations.
+    pub fn on_event(mut self, on_event: impl Fn(&RetryEvent) + Send + Sync + 'static) -> Self {
+        self.on_event = Some(Arc::new(on_event));
+        self
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    pub(crate) fn notify(&self, event: &RetryEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(event);
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_retries", &self.max_retries)
+            .field("on_event", &self.on_event.is_some())
+            .finish()
+    }
+}
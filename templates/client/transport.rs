@@ -0,0 +1,115 @@
+use crate::types::*;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Status and body of an HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+/// Sends encoded method calls over some HTTP stack.
+///
+/// `url` is the full request URL, including the query string of `request`.
+pub trait Transport: Send + Sync {
+    fn send(&self, url: String, request: HttpRequestParts) -> BoxFuture<'_, Result<HttpResponse, TransportError>>;
+
+    /// Waits for `duration` on the runtime the transport runs on, e.g. before a retry.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()>;
+}
+
+impl<T: Transport + ?Sized> Transport for std::sync::Arc<T> {
+    fn send(&self, url: String, request: HttpRequestParts) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+        (**self).send(url, request)
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        (**self).sleep(duration)
+    }
+}
+
+/// Failure of the HTTP stack itself, before any Bot API response was received.
+#[derive(Debug)]
+pub struct TransportError(pub Box<dyn std::error::Error + Send + Sync>);
+
+impl TransportError {
+    pub fn new(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        TransportError(error.into())
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+/// Encodes multipart parts as a `multipart/form-data` body, reading every file.
+///
+/// Returns the `Content-Type` header value along with the body.
+pub fn multipart_body(parts: &[Part]) -> std::io::Result<(String, Vec<u8>)> {
+    let boundary = format!(
+        "------------------------{:016x}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    );
+    let mut body = Vec::new();
+    for part in parts {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        match part {
+            Part::Text { name, value } => {
+                body.extend_from_slice(format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", name).as_bytes());
+                body.extend_from_slice(value.as_bytes());
+            }
+            Part::File { name, file } => {
+                let file_name = file.file_name().unwrap_or_else(|| name.clone()).replace('"', "'");
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                         Content-Type: application/octet-stream\r\n\r\n",
+                        name, file_name
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(&file.read()?);
+            }
+        }
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    Ok((format!("multipart/form-data; boundary={}", boundary), body))
+}
+
+/// Returns the `Content-Type` header and body to send for `request`.
+pub fn http_body(request: &HttpRequestParts) -> std::io::Result<(Option<String>, Vec<u8>)> {
+    match &request.body {
+        RequestBody::Empty => Ok((request.content_type.map(ToOwned::to_owned), Vec::new())),
+        RequestBody::Bytes(bytes) => Ok((request.content_type.map(ToOwned::to_owned), bytes.clone())),
+        RequestBody::Multipart(parts) => multipart_body(parts).map(|(content_type, body)| (Some(content_type), body)),
+    }
+}
+
+/// Polls `future` once and returns its output, for tests of futures that never wait.
+#[cfg(test)]
+pub(crate) fn ready<F: Future>(future: F) -> F::Output {
+    struct NoopWaker;
+
+    impl std::task::Wake for NoopWaker {
+        fn wake(self: std::sync::Arc<Self>) {}
+    }
+
+    let waker = std::task::Waker::from(std::sync::Arc::new(NoopWaker));
+    match Box::pin(future).as_mut().poll(&mut std::task::Context::from_waker(&waker)) {
+        std::task::Poll::Ready(output) => output,
+        std::task::Poll::Pending => panic!("future is not ready"),
+    }
+}
@@ -0,0 +1,194 @@
+use crate::client::*;
+use crate::methods::*;
+use crate::types::*;
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Long-polls `getUpdates`, yielding every update once.
+///
+/// An update is confirmed by the next `getUpdates` call once it has been yielded, as that call
+/// is sent with `offset = update_id + 1`. Updates still buffered when the stream is dropped are
+/// delivered again to the next poller. Call [`UpdateStream::shutdown`] to confirm the yielded
+/// ones before exiting.
+pub struct UpdateStream<T> {
+    bot: Arc<Bot<T>>,
+    offset: Option<Integer>,
+    timeout: Integer,
+    limit: Option<Integer>,
+    allowed_updates: Option<Vec<String>>,
+    buffer: VecDeque<Update>,
+    request: Option<BoxFuture<'static, Result<Vec<Update>, Error>>>,
+    backoff: Duration,
+    sleep: Option<BoxFuture<'static, ()>>,
+}
+
+impl<T: Transport + 'static> UpdateStream<T> {
+    pub fn new(bot: impl Into<Arc<Bot<T>>>) -> Self {
+        UpdateStream {
+            bot: bot.into(),
+            offset: None,
+            timeout: 30,
+            limit: None,
+            allowed_updates: None,
+            buffer: VecDeque::new(),
+            request: None,
+            backoff: MIN_BACKOFF,
+            sleep: None,
+        }
+    }
+
+    /// Long polling timeout in seconds, 30 by default.
+    pub fn timeout(mut self, timeout: Integer) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn limit(mut self, limit: Integer) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn allowed_updates(mut self, allowed_updates: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.allowed_updates = Some(allowed_updates.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Starts from `offset` instead of the first unconfirmed update.
+    pub fn offset(mut self, offset: Integer) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Confirms every yielded update, so that they are not delivered again.
+    pub async fn shutdown(self) -> Result<(), Error> {
+        if let Some(offset) = self.offset {
+            let method = GetUpdates::builder().offset(offset).limit(1).timeout(0).build();
+            self.bot.execute(method).await?;
+        }
+        Ok(())
+    }
+
+    fn get_updates(&self) -> GetUpdates {
+        let mut builder = GetUpdates::builder().timeout(self.timeout);
+        if let Some(offset) = self.offset {
+            builder = builder.offset(offset);
+        }
+        if let Some(limit) = self.limit {
+            builder = builder.limit(limit);
+        }
+        if let Some(allowed_updates) = self.allowed_updates.clone() {
+            builder = builder.allowed_updates(allowed_updates);
+        }
+        builder.build()
+    }
+}
+
+impl<T: Transport + 'static> Stream for UpdateStream<T> {
+    type Item = Result<Update, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+        let stream = self.get_mut();
+        loop {
+            if let Some(update) = stream.buffer.pop_front() {
+                stream.offset = Some(update.update_id + 1);
+                return Poll::Ready(Some(Ok(update)));
+            }
+            if let Some(sleep) = &mut stream.sleep {
+                match sleep.as_mut().poll(context) {
+                    Poll::Ready(()) => stream.sleep = None,
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+            if stream.request.is_none() {
+                let bot = stream.bot.clone();
+                let method = stream.get_updates();
+                stream.request = Some(Box::pin(async move { bot.execute(method).await }));
+            }
+            let result = match stream.request.as_mut().map(|request| request.as_mut().poll(context)) {
+                Some(Poll::Ready(result)) => result,
+                _ => return Poll::Pending,
+            };
+            stream.request = None;
+            match result {
+                Ok(updates) => {
+                    stream.backoff = MIN_BACKOFF;
+                    stream.buffer.extend(updates);
+                }
+                Err(error) => {
+                    let bot = stream.bot.clone();
+                    let backoff = stream.backoff;
+                    stream.sleep = Some(Box::pin(async move { bot.transport().sleep(backoff).await }));
+                    stream.backoff = (backoff * 2).min(MAX_BACKOFF);
+                    return Poll::Ready(Some(Err(error)));
+                }
+            }
+        }
+    }
+}
+
+impl<T: Transport + 'static> Bot<T> {
+    /// Turns this client into a long-polling [`UpdateStream`].
+    pub fn into_update_stream(self) -> UpdateStream<T> {
+        UpdateStream::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Answers requests with `responses` in order, then fails, and records every request and
+    /// sleep.
+    #[derive(Default)]
+    struct Script {
+        responses: Mutex<VecDeque<&'static str>>,
+        requests: Mutex<Vec<serde_json::Value>>,
+        sleeps: Mutex<Vec<Duration>>,
+    }
+
+    impl Transport for Script {
+        fn send(&self, _url: String, request: HttpRequestParts) -> BoxFuture<'_, Result<HttpResponse, TransportError>> {
+            let (_, body) = http_body(&request).unwrap();
+            self.requests.lock().unwrap().push(serde_json::from_slice(&body).unwrap());
+            let response = self.responses.lock().unwrap().pop_front();
+            Box::pin(async move {
+                match response {
+                    Some(body) => Ok(HttpResponse { status: 200, body: body.as_bytes().to_vec() }),
+                    None => Err(TransportError::new("offline")),
+                }
+            })
+        }
+
+        fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+            self.sleeps.lock().unwrap().push(duration);
+            Box::pin(async {})
+        }
+    }
+
+    fn poll_next<T: Transport + 'static>(stream: &mut UpdateStream<T>) -> Option<Result<Update, Error>> {
+        ready(std::future::poll_fn(|context| Pin::new(&mut *stream).poll_next(context)))
+    }
+
+    #[test]
+    fn confirms_yielded_updates_and_backs_off_on_errors() {
+        let script = Arc::new(Script::default());
+        script.responses.lock().unwrap().push_back(r#"{"ok":true,"result":[{"update_id":1},{"update_id":2}]}"#);
+        let mut stream = Bot::new("token", script.clone()).into_update_stream().timeout(5);
+        let ids: Vec<_> = (0..2).map(|_| poll_next(&mut stream).unwrap().unwrap().update_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(poll_next(&mut stream).unwrap().is_err());
+        assert!(poll_next(&mut stream).unwrap().is_err());
+        let requests = script.requests.lock().unwrap();
+        assert_eq!(requests[0], serde_json::json!({ "timeout": 5 }));
+        assert_eq!(requests[1], serde_json::json!({ "offset": 3, "timeout": 5 }));
+        assert_eq!(*script.sleeps.lock().unwrap(), vec![MIN_BACKOFF]);
+    }
+}
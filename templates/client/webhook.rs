@@ -0,0 +1,90 @@
+use crate::types::*;
+use std::future::Future;
+
+/// A method call returned in the body of a webhook response, as described under "Making
+/// requests when getting updates".
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookReply(serde_json::Value);
+
+impl WebhookReply {
+    /// Serializes `method` with its name in the `method` field. Returns `None` for calls that
+    /// upload files, which a webhook response cannot carry.
+    pub fn new<M: Method>(method: &M) -> Option<Self> {
+        let mut attachments = Vec::new();
+        method.attachments(&mut attachments);
+        if !attachments.is_empty() {
+            return None;
+        }
+        let mut value = match serde_json::to_value(method).ok()? {
+            serde_json::Value::Null => serde_json::Value::Object(serde_json::Map::new()),
+            value => value,
+        };
+        value
+            .as_object_mut()?
+            .insert("method".to_owned(), serde_json::Value::String(M::name().to_owned()));
+        Some(WebhookReply(value))
+    }
+
+    pub fn to_json(&self) -> Vec<u8> {
+        self.0.to_string().into_bytes()
+    }
+}
+
+/// Status, `Content-Type` and body to answer a webhook request with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookResponse {
+    pub status: u16,
+    pub content_type: Option<&'static str>,
+    pub body: Vec<u8>,
+}
+
+impl WebhookResponse {
+    fn empty(status: u16) -> Self {
+        WebhookResponse {
+            status,
+            content_type: None,
+            body: Vec::new(),
+        }
+    }
+}
+
+/// Receives updates sent to a webhook set up with `https://<host>/<...>/<secret>`.
+///
+/// Requests whose last path segment is not `secret` are answered with 404, so the URL itself
+/// authenticates Telegram.
+pub struct Webhook<F> {
+    secret: String,
+    handler: F,
+}
+
+impl<F, Fut> Webhook<F>
+where
+    F: Fn(Update) -> Fut,
+    Fut: Future<Output = Option<WebhookReply>>,
+{
+    pub fn new(secret: impl Into<String>, handler: F) -> Self {
+        Webhook {
+            secret: secret.into(),
+            handler,
+        }
+    }
+
+    /// Handles a `POST` of `body` to `path`, independently of any HTTP server.
+    pub async fn handle(&self, path: &str, body: &[u8]) -> WebhookResponse {
+        if path.trim_end_matches('/').rsplit('/').next() != Some(self.secret.as_str()) {
+            return WebhookResponse::empty(404);
+        }
+        let update = match serde_json::from_slice::<Update>(body) {
+            Ok(update) => update,
+            Err(_) => return WebhookResponse::empty(400),
+        };
+        match (self.handler)(update).await {
+            Some(reply) => WebhookResponse {
+                status: 200,
+                content_type: Some("application/json"),
+                body: reply.to_json(),
+            },
+            None => WebhookResponse::empty(200),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::types::*;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// A file to send, either one Telegram can already get to or one to upload.
+///
+/// `FileId` and `Url` are sent as plain strings. The other variants are uploads: they force a
+/// `multipart/form-data` request, and are referenced from the parameters as `attach://<name>`.
+#[derive(Clone)]
+pub enum InputFile {
+    /// `file_id` of a file that already exists on the Telegram servers.
+    FileId(String),
+    /// HTTP URL for Telegram to get the file from the Internet.
+    Url(String),
+    /// File on the local disk, uploaded under its own file name.
+    Path(PathBuf),
+    /// Contents held in memory, uploaded under `name`.
+    Memory { name: String, bytes: Vec<u8> },
+    /// Contents read when the request is first sent, uploaded under `name`.
+    Reader {
+        name: String,
+        reader: Arc<Mutex<ReadOnce>>,
+    },
+}
+
+/// A reader read to the end only once, whose contents are then kept in memory, so that a
+/// request uploading them can be sent again, e.g. when it is retried.
+pub struct ReadOnce {
+    reader: Option<Box<dyn Read + Send>>,
+    bytes: Vec<u8>,
+}
+
+impl ReadOnce {
+    pub fn new(reader: impl Read + Send + 'static) -> Self {
+        ReadOnce {
+            reader: Some(Box::new(reader)),
+            bytes: Vec::new(),
+        }
+    }
+
+    /// Contents of the reader, read on the first call.
+    pub fn read(&mut self) -> io::Result<Vec<u8>> {
+        if let Some(mut reader) = self.reader.take() {
+            let mut bytes = Vec::new();
+            if let Err(error) = reader.read_to_end(&mut bytes) {
+                self.reader = Some(reader);
+                return Err(error);
+            }
+            self.bytes = bytes;
+        }
+        Ok(self.bytes.clone())
+    }
+}
+
+impl InputFile {
+    pub fn file_id(file_id: impl Into<String>) -> Self {
+        InputFile::FileId(file_id.into())
+    }
+
+    pub fn url(url: impl Into<String>) -> Self {
+        InputFile::Url(url.into())
+    }
+
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        InputFile::Path(path.into())
+    }
+
+    pub fn memory(name: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+        InputFile::Memory {
+            name: name.into(),
+            bytes: bytes.into(),
+        }
+    }
+
+    pub fn reader(name: impl Into<String>, reader: impl Read + Send + 'static) -> Self {
+        InputFile::Reader {
+            name: name.into(),
+            reader: Arc::new(Mutex::new(ReadOnce::new(reader))),
+        }
+    }
+
+    /// Whether this file has to be uploaded with `multipart/form-data`.
+    pub fn is_upload(&self) -> bool {
+        match self {
+            InputFile::FileId(_) | InputFile::Url(_) => false,
+            InputFile::Path(_) | InputFile::Memory { .. } | InputFile::Reader { .. } => true,
+        }
+    }
+
+    /// File name to report in the multipart part, for uploads.
+    pub fn file_name(&self) -> Option<String> {
+        match self {
+            InputFile::FileId(_) | InputFile::Url(_) => None,
+            InputFile::Path(path) => path.file_name().map(|name| name.to_string_lossy().into_owned()),
+            InputFile::Memory { name, .. } | InputFile::Reader { name, .. } => Some(name.clone()),
+        }
+    }
+
+    /// Reads the contents of an upload; this is the only place that performs IO.
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            InputFile::FileId(_) | InputFile::Url(_) => {
+                Err(io::Error::new(io::ErrorKind::InvalidInput, "not an upload"))
+            }
+            InputFile::Path(path) => std::fs::read(path),
+            InputFile::Memory { bytes, .. } => Ok(bytes.clone()),
+            InputFile::Reader { reader, .. } => reader
+                .lock()
+                .map_err(|_| io::Error::new(io::ErrorKind::Other, "reader lock poisoned"))?
+                .read(),
+        }
+    }
+}
+
+impl fmt::Debug for InputFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputFile::FileId(file_id) => f.debug_tuple("FileId").field(file_id).finish(),
+            InputFile::Url(url) => f.debug_tuple("Url").field(url).finish(),
+            InputFile::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            InputFile::Memory { name, bytes } => f
+                .debug_struct("Memory")
+                .field("name", name)
+                .field("bytes", &bytes.len())
+                .finish(),
+            InputFile::Reader { name, .. } => f.debug_struct("Reader").field("name", name).finish(),
+        }
+    }
+}
+
+impl PartialEq for InputFile {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (InputFile::FileId(a), InputFile::FileId(b)) => a == b,
+            (InputFile::Url(a), InputFile::Url(b)) => a == b,
+            (InputFile::Path(a), InputFile::Path(b)) => a == b,
+            (
+                InputFile::Memory { name, bytes },
+                InputFile::Memory {
+                    name: other_name,
+                    bytes: other_bytes,
+                },
+            ) => name == other_name && bytes == other_bytes,
+            (InputFile::Reader { reader, .. }, InputFile::Reader { reader: other, .. }) => {
+                Arc::ptr_eq(reader, other)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl serde::Serialize for InputFile {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            InputFile::FileId(string) | InputFile::Url(string) => serializer.serialize_str(string),
+            _ => match next_attachment_name() {
+                Some(name) => serializer.serialize_str(&format!("attach://{}", name)),
+                None => Err(serde::ser::Error::custom("uploads can only be serialized by `encode`")),
+            },
+        }
+    }
+}
+
+impl Attachments for InputFile {
+    fn attachments(&self, attachments: &mut Vec<Attachment>) {
+        if self.is_upload() {
+            attachments.push(Attachment {
+                name: format!("file{}", attachments.len()),
+                file: self.clone(),
+            });
+        }
+    }
+}
+
+/// Reads a file as the Bot API receives it. Uploads only keep their `attach://` name, as a
+/// `Memory` file without contents.
+#[cfg(feature = "serde-methods")]
+impl<'de> serde::Deserialize<'de> for InputFile {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Ok(if let Some(name) = string.strip_prefix("attach://") {
+            InputFile::memory(name, Vec::new())
+        } else if string.starts_with("http://") || string.starts_with("https://") {
+            InputFile::Url(string)
+        } else {
+            InputFile::FileId(string)
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use crate::types::*;
+
+/// A Bot API method, sent as a request to `https://api.telegram.org/bot<token>/<name>`.
+pub trait Method: serde::Serialize + Attachments {
+    /// Type of the `result` field of a successful response.
+    type Response: serde::de::DeserializeOwned;
+
+    /// Name of the method, as used in the request URL.
+    fn name() -> &'static str;
+
+    /// Encodes this call without performing any IO, see [`encode`].
+    fn to_request(&self, encoding: Encoding) -> HttpRequestParts
+    where
+        Self: Sized,
+    {
+        encode(self, encoding)
+    }
+
+    /// Points this call at the supergroup a group migrated to, when it has a `chat_id`.
+    /// Returns whether there was anything to change.
+    fn migrate_chat(&mut self, _migrate_to_chat_id: Integer) -> bool {
+        false
+    }
+
+    /// Parses the body of a response to this method.
+    fn parse_response(body: &[u8]) -> serde_json::Result<Result<Self::Response, ApiError>> {
+        serde_json::from_slice::<MethodResponse<Self>>(body).map(ApiResponse::into_result)
+    }
+}
@@ -0,0 +1,286 @@
+use crate::types::*;
+use std::cell::Cell;
+
+/// How the parameters of a method are passed, as listed under "Making requests".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// URL query string of a `GET` request.
+    Query,
+    /// `application/x-www-form-urlencoded` body.
+    UrlEncoded,
+    /// `application/json` body.
+    Json,
+    /// `multipart/form-data` body, the only one able to upload files.
+    Multipart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// A method call ready to be sent by any HTTP library.
+///
+/// The request goes to `https://api.telegram.org/bot<token>/<method>`, followed by `?<query>`
+/// when `query` is not empty.
+#[derive(Debug, Clone)]
+pub struct HttpRequestParts {
+    pub http_method: HttpMethod,
+    pub method: &'static str,
+    pub query: String,
+    /// `Content-Type` header; `None` for multipart bodies, whose boundary is picked by the sender.
+    pub content_type: Option<&'static str>,
+    pub body: RequestBody,
+}
+
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    Empty,
+    Bytes(Vec<u8>),
+    Multipart(Vec<Part>),
+}
+
+/// A single field of a `multipart/form-data` body.
+#[derive(Debug, Clone)]
+pub enum Part {
+    Text { name: String, value: String },
+    File { name: String, file: InputFile },
+}
+
+/// A file to be uploaded under `name`, referenced from parameters as `attach://<name>`. Uploads
+/// are named `file0`, `file1`, ... in the order they are collected, which is also the order they
+/// are serialized in.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub name: String,
+    pub file: InputFile,
+}
+
+/// Implemented by every value that may carry files to upload.
+pub trait Attachments {
+    /// Collects the files that have to be sent as multipart parts.
+    fn attachments(&self, _attachments: &mut Vec<Attachment>) {}
+}
+
+impl<T: Attachments> Attachments for Option<T> {
+    fn attachments(&self, attachments: &mut Vec<Attachment>) {
+        if let Some(value) = self {
+            value.attachments(attachments);
+        }
+    }
+}
+
+impl<T: Attachments> Attachments for Vec<T> {
+    fn attachments(&self, attachments: &mut Vec<Attachment>) {
+        for value in self {
+            value.attachments(attachments);
+        }
+    }
+}
+
+impl<T: Attachments> Attachments for Box<T> {
+    fn attachments(&self, attachments: &mut Vec<Attachment>) {
+        (**self).attachments(attachments);
+    }
+}
+
+/// Encodes `method` with `encoding`, switching to multipart whenever it carries files.
+///
+/// In every encoding but JSON, parameters that are objects or arrays are sent as JSON-encoded
+/// strings.
+pub fn encode<M: Method>(method: &M, encoding: Encoding) -> HttpRequestParts {
+    let mut attachments = Vec::new();
+    method.attachments(&mut attachments);
+    let encoding = if attachments.is_empty() {
+        encoding
+    } else {
+        Encoding::Multipart
+    };
+    let value = UPLOADS_SERIALIZED.with(|uploads| {
+        let outer = uploads.replace(Some(0));
+        let value = serde_json::to_value(method);
+        uploads.set(outer);
+        value
+    });
+    let value = match value.expect("method parameters serialize to JSON") {
+        serde_json::Value::Null => serde_json::Value::Object(serde_json::Map::new()),
+        value => value,
+    };
+    let mut request = HttpRequestParts {
+        http_method: HttpMethod::Post,
+        method: M::name(),
+        query: String::new(),
+        content_type: None,
+        body: RequestBody::Empty,
+    };
+    match encoding {
+        Encoding::Query => {
+            request.http_method = HttpMethod::Get;
+            request.query = form_urlencode(&params(value));
+        }
+        Encoding::UrlEncoded => {
+            request.content_type = Some("application/x-www-form-urlencoded");
+            request.body = RequestBody::Bytes(form_urlencode(&params(value)).into_bytes());
+        }
+        Encoding::Json => {
+            request.content_type = Some("application/json");
+            request.body = RequestBody::Bytes(value.to_string().into_bytes());
+        }
+        Encoding::Multipart => {
+            let mut parts: Vec<_> = params(value)
+                .into_iter()
+                .map(|(name, value)| Part::Text { name, value })
+                .collect();
+            parts.extend(attachments.into_iter().map(|attachment| Part::File {
+                name: attachment.name,
+                file: attachment.file,
+            }));
+            request.body = RequestBody::Multipart(parts);
+        }
+    }
+    request
+}
+
+thread_local! {
+    /// Number of uploads `encode` has serialized so far on this thread, or `None` outside of it.
+    static UPLOADS_SERIALIZED: Cell<Option<usize>> = Cell::new(None);
+}
+
+/// Name of the next upload serialized by `encode`, or `None` when serializing outside of it,
+/// where there is no request for the upload to be attached to.
+pub(crate) fn next_attachment_name() -> Option<String> {
+    UPLOADS_SERIALIZED.with(|uploads| {
+        let index = uploads.get()?;
+        uploads.set(Some(index + 1));
+        Some(format!("file{}", index))
+    })
+}
+
+/// Flattens top-level parameters to strings, JSON-encoding objects and arrays.
+fn params(value: serde_json::Value) -> Vec<(String, String)> {
+    let object = match value {
+        serde_json::Value::Object(object) => object,
+        _ => panic!("method parameters must serialize to a JSON object"),
+    };
+    object
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(string) => string,
+                value => value.to_string(),
+            };
+            Some((name, value))
+        })
+        .collect()
+}
+
+fn form_urlencode(params: &[(String, String)]) -> String {
+    let mut encoded = String::new();
+    for (name, value) in params {
+        if !encoded.is_empty() {
+            encoded.push('&');
+        }
+        percent_encode(name, &mut encoded);
+        encoded.push('=');
+        percent_encode(value, &mut encoded);
+    }
+    encoded
+}
+
+fn percent_encode(string: &str, encoded: &mut String) {
+    for byte in string.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct SendFiles {
+        chat_id: Integer,
+        files: Vec<InputFile>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        caption: Option<String>,
+    }
+
+    impl Attachments for SendFiles {
+        fn attachments(&self, attachments: &mut Vec<Attachment>) {
+            self.files.attachments(attachments);
+        }
+    }
+
+    impl Method for SendFiles {
+        type Response = bool;
+
+        fn name() -> &'static str {
+            "sendFiles"
+        }
+    }
+
+    fn send_files(files: Vec<InputFile>) -> SendFiles {
+        SendFiles {
+            chat_id: 1,
+            files,
+            caption: Some("a b&c".to_owned()),
+        }
+    }
+
+    #[test]
+    fn encodes_parameters_as_asked() {
+        let method = send_files(vec![InputFile::file_id("a")]);
+        let request = encode(&method, Encoding::UrlEncoded);
+        assert_eq!(request.content_type, Some("application/x-www-form-urlencoded"));
+        match request.body {
+            RequestBody::Bytes(body) => {
+                assert_eq!(String::from_utf8(body).unwrap(), "caption=a+b%26c&chat_id=1&files=%5B%22a%22%5D")
+            }
+            body => panic!("expected bytes, got {:?}", body),
+        }
+        let request = encode(&method, Encoding::Query);
+        assert_eq!((request.http_method, request.query.as_str()), (HttpMethod::Get, "caption=a+b%26c&chat_id=1&files=%5B%22a%22%5D"));
+    }
+
+    #[test]
+    fn names_uploads_by_their_position() {
+        let method = send_files(vec![
+            InputFile::file_id("a"),
+            InputFile::memory("b.txt", "b"),
+            InputFile::memory("c.txt", "c"),
+        ]);
+        for _ in 0..2 {
+            let parts = match encode(&method, Encoding::Json).body {
+                RequestBody::Multipart(parts) => parts,
+                body => panic!("expected multipart, got {:?}", body),
+            };
+            let files = parts.iter().find_map(|part| match part {
+                Part::Text { name, value } if name == "files" => Some(value.as_str()),
+                _ => None,
+            });
+            assert_eq!(files, Some(r#"["a","attach://file0","attach://file1"]"#));
+            let uploads: Vec<_> = parts
+                .iter()
+                .filter_map(|part| match part {
+                    Part::File { name, file } => Some((name.as_str(), file.file_name())),
+                    Part::Text { .. } => None,
+                })
+                .collect();
+            assert_eq!(uploads, vec![("file0", Some("b.txt".to_owned())), ("file1", Some("c.txt".to_owned()))]);
+        }
+    }
+
+    #[test]
+    fn uploads_only_serialize_in_a_request() {
+        assert!(serde_json::to_value(send_files(vec![InputFile::file_id("a")])).is_ok());
+        assert!(serde_json::to_value(send_files(vec![InputFile::memory("b.txt", "b")])).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use crate::types::*;
+use std::fmt;
+
+/// Body of every Bot API response, as described under "Making requests".
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ApiResponse<T> {
+    pub ok: bool,
+    pub result: Option<T>,
+    pub description: Option<String>,
+    pub error_code: Option<Integer>,
+    pub parameters: Option<ResponseParameters>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn into_result(self) -> Result<T, ApiError> {
+        match self.result {
+            Some(result) if self.ok => Ok(result),
+            _ => {
+                let parameters = self.parameters;
+                Err(ApiError {
+                    error_code: self.error_code.unwrap_or_default(),
+                    description: self.description.unwrap_or_default(),
+                    retry_after: parameters.as_ref().and_then(|parameters| parameters.retry_after),
+                    migrate_to_chat_id: parameters.and_then(|parameters| parameters.migrate_to_chat_id),
+                })
+            }
+        }
+    }
+}
+
+impl<T> From<ApiResponse<T>> for Result<T, ApiError> {
+    fn from(response: ApiResponse<T>) -> Self {
+        response.into_result()
+    }
+}
+
+/// An unsuccessful response, with its `ResponseParameters` flattened in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiError {
+    pub error_code: Integer,
+    pub description: String,
+    /// Seconds to wait before repeating the request, when flood control was exceeded.
+    pub retry_after: Option<Integer>,
+    /// New identifier of a group that was migrated to a supergroup.
+    pub migrate_to_chat_id: Option<Integer>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (error code {})", self.description, self.error_code)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Response to the method `M`.
+pub type MethodResponse<M> = ApiResponse<<M as Method>::Response>;
@@ -0,0 +1,33 @@
+/// A generated type as described in the documentation, to walk values without going through
+/// serde.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeInfo {
+    pub name: &'static str,
+    pub doc: &'static str,
+    /// `None` for unions the documentation does not name, such as `ChatIdOrUsername`.
+    pub doc_url: Option<&'static str>,
+    pub shape: Shape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Struct(&'static [FieldInfo]),
+    Union(&'static [VariantInfo]),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// Name of the field in JSON, e.g. `type` for the Rust field `type_`.
+    pub name: &'static str,
+    /// Type of the field, as written in the documentation, e.g. `Array of PhotoSize`.
+    pub telegram_type: &'static str,
+    pub optional: bool,
+    pub doc: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariantInfo {
+    /// Name of the Rust variant.
+    pub name: &'static str,
+    pub telegram_type: &'static str,
+}
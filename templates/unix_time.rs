@@ -0,0 +1,119 @@
+use crate::types::*;
+use std::convert::TryFrom;
+
+/// A point in time, as the number of seconds since the Unix epoch.
+///
+/// Serialized as a plain integer, exactly as Telegram sends it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UnixTime(pub Integer);
+
+impl UnixTime {
+    /// Seconds since the Unix epoch.
+    pub fn seconds(self) -> Integer {
+        self.0
+    }
+}
+
+impl From<Integer> for UnixTime {
+    fn from(seconds: Integer) -> Self {
+        UnixTime(seconds)
+    }
+}
+
+impl From<UnixTime> for Integer {
+    fn from(time: UnixTime) -> Self {
+        time.0
+    }
+}
+
+/// Error of a conversion between `UnixTime` and a time type that can't represent the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnixTimeOutOfRange;
+
+impl std::fmt::Display for UnixTimeOutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("time out of the range of the target type")
+    }
+}
+
+impl std::error::Error for UnixTimeOutOfRange {}
+
+impl TryFrom<std::time::SystemTime> for UnixTime {
+    type Error = UnixTimeOutOfRange;
+
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        let seconds = match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => Integer::try_from(duration.as_secs()),
+            Err(error) => Integer::try_from(error.duration().as_secs()).map(|seconds| -seconds),
+        };
+        seconds.map(UnixTime).map_err(|_| UnixTimeOutOfRange)
+    }
+}
+
+impl TryFrom<UnixTime> for std::time::SystemTime {
+    type Error = UnixTimeOutOfRange;
+
+    fn try_from(time: UnixTime) -> Result<Self, Self::Error> {
+        let seconds = std::time::Duration::from_secs(time.0.unsigned_abs() as u64);
+        let time = if time.0 >= 0 {
+            std::time::UNIX_EPOCH.checked_add(seconds)
+        } else {
+            std::time::UNIX_EPOCH.checked_sub(seconds)
+        };
+        time.ok_or(UnixTimeOutOfRange)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for UnixTime {
+    fn from(time: chrono::DateTime<chrono::Utc>) -> Self {
+        UnixTime(time.timestamp() as Integer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<UnixTime> for chrono::DateTime<chrono::Utc> {
+    type Error = UnixTimeOutOfRange;
+
+    fn try_from(time: UnixTime) -> Result<Self, Self::Error> {
+        chrono::DateTime::from_timestamp(time.0 as i64, 0).ok_or(UnixTimeOutOfRange)
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for UnixTime {
+    fn from(time: time::OffsetDateTime) -> Self {
+        UnixTime(time.unix_timestamp() as Integer)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<UnixTime> for time::OffsetDateTime {
+    type Error = UnixTimeOutOfRange;
+
+    fn try_from(time: UnixTime) -> Result<Self, Self::Error> {
+        time::OffsetDateTime::from_unix_timestamp(time.0 as i64).map_err(|_| UnixTimeOutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn system_time_round_trips() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+        assert_eq!(UnixTime::try_from(time), Ok(UnixTime(1_500_000_000)));
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(86_400);
+        assert_eq!(SystemTime::try_from(UnixTime(-86_400)), Ok(before_epoch));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn out_of_range_times_are_errors() {
+        let time = chrono::DateTime::<chrono::Utc>::try_from(UnixTime(Integer::MAX));
+        assert_eq!(time, Err(UnixTimeOutOfRange));
+    }
+}
@@ -0,0 +1,65 @@
+//! Generates the crate from `api.html` and has cargo build and test it, which also runs the unit
+//! tests of the templates.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// What the crate including the generated code defines itself: primitives the documentation
+/// does not describe, and the `CallbackGame` placeholder.
+const PRELUDE: &str = "
+pub type Integer = i64;
+pub type Float = f64;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallbackGame;
+";
+
+const LIB: &str = "#[macro_use]
+extern crate serde_derive;
+
+pub mod client;
+pub mod methods;
+pub mod types;
+";
+
+fn run(command: &mut Command) {
+    let status = command.status().expect("cargo runs");
+    assert!(status.success(), "{:?} failed", command);
+}
+
+fn generate(dir: &Path, args: &[&str]) {
+    if dir.exists() {
+        fs::remove_dir_all(dir).unwrap();
+    }
+    let src = dir.join("src");
+    fs::create_dir_all(&src).unwrap();
+    let api = concat!(env!("CARGO_MANIFEST_DIR"), "/api.html");
+    run(Command::new(env!("CARGO_BIN_EXE_telegram-bot-types-parser")).arg(api).arg(&src).args(args));
+    let snippet = fs::read_to_string(src.join("Cargo.snippet.toml")).unwrap();
+    let manifest = format!("[package]\nname = \"generated\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n{}", snippet);
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    fs::remove_file(src.join("Cargo.snippet.toml")).unwrap();
+    let mut types = fs::read_to_string(src.join("types/mod.rs")).unwrap();
+    types.push_str(PRELUDE);
+    fs::write(src.join("types/mod.rs"), types).unwrap();
+    fs::write(src.join("lib.rs"), LIB).unwrap();
+}
+
+fn cargo(dir: &Path, args: &[&str]) {
+    let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated-target");
+    run(Command::new(env!("CARGO")).args(args).current_dir(dir).env("CARGO_TARGET_DIR", target));
+}
+
+#[test]
+fn generated_crate_builds_and_passes_its_tests() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("generated");
+    generate(&dir, &[]);
+    cargo(&dir, &["check", "--all-targets"]);
+    cargo(&dir, &["check", "--features", "serde-methods"]);
+    cargo(&dir, &["test", "--all-features"]);
+
+    generate(&dir, &["--sections", "--api-baseline", "4.0", "--non-exhaustive"]);
+    cargo(&dir, &["check", "--all-targets"]);
+    cargo(&dir, &["test", "--all-features"]);
+}